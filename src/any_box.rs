@@ -0,0 +1,22 @@
+use super::AnyRecvError;
+use core::any::*;
+
+/// Downcasts the boxed values returned by methods such as 
+/// [AnyReceiver::recv_any](crate::AnyReceiver::recv_any), so that they can 
+/// be chained with `?` like a typed receive.
+pub trait AnyBoxExt
+{
+    /// Takes the value out of the box if it is a `T`. Otherwise the box is 
+    /// returned in [AnyRecvError::WrongType], as [AnyRecvError::wrong_type] 
+    /// does.
+    fn downcast_or_err<T: 'static>(self) -> Result<T, AnyRecvError>;
+}
+
+impl AnyBoxExt for Box<dyn Any>
+{
+    #[inline]
+    fn downcast_or_err<T: 'static>(self) -> Result<T, AnyRecvError>
+    {
+        self.downcast().map(|t| *t).map_err(AnyRecvError::wrong_type)
+    }
+}
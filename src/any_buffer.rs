@@ -0,0 +1,179 @@
+use core::any::*;
+
+/// Storage for the values a [BufferedReceiver](crate::BufferedReceiver) 
+/// receives but does not hand out yet. [TypeBuffer](crate::TypeBuffer) is used by default; 
+/// implement this to change how values are held, e.g. to keep one insertion
+/// order across all types, or to bound the buffer. [TypeBuffer](crate::TypeBuffer) replaces
+/// the `dfb::Dfb` buffer used before 0.5 and has the same `new`, 
+/// `insert_dyn` and `remove` methods.
+///
+/// Only a handful of methods are required. The rest are built on them and 
+/// may be overridden where the buffer can do better.
+pub trait AnyBuffer
+{
+    /// Adds a value to the buffer.
+    fn insert_dyn(&mut self, t: Box<dyn Any>);
+
+    /// The type of the value [AnyBuffer::remove_matching] would remove for
+    /// `pred`, if any buffered type passes it.
+    fn find_type(&self, pred: &dyn Fn(TypeId) -> bool) -> Option<TypeId>;
+
+    /// Removes the oldest value with the given [TypeId] that passes `pred`,
+    /// if any.
+    fn remove_where_dyn(&mut self, type_id: TypeId, pred: &dyn Fn(&dyn Any) -> bool) -> Option<Box<dyn Any>>;
+
+    /// Iterates over the values with the given [TypeId], oldest first, 
+    /// without removing them.
+    fn iter_dyn(&self, type_id: TypeId) -> Box<dyn Iterator<Item = &dyn Any> + '_>;
+
+    /// Returns a mutable reference to the oldest value with the given 
+    /// [TypeId], if any. Its position in the buffer is unchanged.
+    fn peek_dyn_mut(&mut self, type_id: TypeId) -> Option<&mut dyn Any>;
+
+    /// Total number of buffered values.
+    fn len(&self) -> usize;
+
+    /// Number of buffered values with the given [TypeId].
+    fn len_of(&self, type_id: TypeId) -> usize;
+
+    /// Removes every buffered value.
+    fn clear(&mut self);
+
+    /// Returns a reference to the oldest value with the given [TypeId], if 
+    /// any.
+    #[inline]
+    fn peek_dyn(&self, type_id: TypeId) -> Option<&dyn Any>
+    {
+        self.iter_dyn(type_id).next()
+    }
+
+    /// Whether no values are buffered.
+    #[inline]
+    fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+
+    /// Whether any values with the given [TypeId] are buffered.
+    #[inline]
+    fn contains(&self, type_id: TypeId) -> bool
+    {
+        self.peek_dyn(type_id).is_some()
+    }
+
+    /// The distinct types of the buffered values, in the order 
+    /// [AnyBuffer::find_type] considers them.
+    fn types(&self) -> Vec<TypeId>
+    {
+        let mut types = Vec::new();
+        while let Some(type_id) = self.find_type(&|type_id| !types.contains(&type_id))
+        {
+            types.push(type_id);
+        }
+        types
+    }
+
+    /// Makes room for values of `additional` more types, if the buffer 
+    /// preallocates at all. Does nothing by default.
+    #[inline]
+    fn reserve(&mut self, _additional: usize) {}
+
+    /// Removes the oldest value with the given [TypeId], if any.
+    #[inline]
+    fn remove_dyn(&mut self, type_id: TypeId) -> Option<Box<dyn Any>>
+    {
+        self.remove_where_dyn(type_id, &|_| true)
+    }
+
+    /// Removes the oldest value of type `T`, if any.
+    #[inline]
+    fn remove<T: Any>(&mut self) -> Option<T>
+    {
+        self.remove_dyn(TypeId::of::<T>())
+            .map(|t| *t.downcast().expect("buffer returned a value of the wrong type"))
+    }
+
+    /// Returns a reference to the oldest value of type `T`, if any.
+    #[inline]
+    fn peek<T: Any>(&self) -> Option<&T>
+    {
+        self.peek_dyn(TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Returns a mutable reference to the oldest value of type `T`, if any.
+    #[inline]
+    fn peek_mut<T: Any>(&mut self) -> Option<&mut T>
+    {
+        self.peek_dyn_mut(TypeId::of::<T>())?.downcast_mut()
+    }
+
+    /// Removes the oldest value of type `T` that passes `pred`, if any.
+    #[inline]
+    fn remove_where<T: Any>(&mut self, pred: impl Fn(&T) -> bool) -> Option<T>
+    {
+        self.remove_where_dyn(TypeId::of::<T>(), &|t| t.downcast_ref().is_some_and(&pred))
+            .map(|t| *t.downcast().expect("buffer returned a value of the wrong type"))
+    }
+
+    /// Removes a value of the first buffered type that passes `pred`. See
+    /// [AnyBuffer::find_type].
+    #[inline]
+    fn remove_matching(&mut self, pred: impl Fn(TypeId) -> bool) -> Option<Box<dyn Any>>
+    {
+        let type_id = self.find_type(&pred)?;
+        self.remove_dyn(type_id)
+    }
+
+    /// Removes the first buffered value, of any type, that passes `pred`. By
+    /// default this drains the buffer and inserts every other value back.
+    fn remove_any_where(&mut self, pred: &dyn Fn(&dyn Any) -> bool) -> Option<Box<dyn Any>>
+    {
+        let mut found = None;
+        for t in self.drain()
+        {
+            match found.is_none() && pred(t.as_ref())
+            {
+                true => found = Some(t),
+                false => self.insert_dyn(t)
+            }
+        }
+        found
+    }
+
+    /// Removes every buffered value with the given [TypeId], returning how 
+    /// many were removed.
+    fn clear_of(&mut self, type_id: TypeId) -> usize
+    {
+        let mut removed = 0;
+        while self.remove_dyn(type_id).is_some()
+        {
+            removed += 1;
+        }
+        removed
+    }
+
+    /// Keeps only the buffered values that pass `pred`, which is given each
+    /// value and its [TypeId]. By default this drains the buffer and inserts
+    /// the kept values back.
+    fn retain(&mut self, pred: &mut dyn FnMut(TypeId, &dyn Any) -> bool)
+    {
+        for t in self.drain()
+        {
+            if pred(t.as_ref().type_id(), t.as_ref())
+            {
+                self.insert_dyn(t);
+            }
+        }
+    }
+
+    /// Removes and returns every buffered value.
+    fn drain(&mut self) -> Vec<Box<dyn Any>>
+    {
+        let mut values = Vec::with_capacity(self.len());
+        while let Some(t) = self.remove_matching(|_type_id| true)
+        {
+            values.push(t);
+        }
+        values
+    }
+}
@@ -0,0 +1,84 @@
+use super::{mpsc::{self, Sender, Receiver}, trace, AnyRecvError};
+use core::any::*;
+use std::{sync::Arc, time::Duration};
+
+type ArcAny = Arc<dyn Any + Send + Sync>;
+
+/// An [mpsc::channel] that supports dynamic typing and shares values as 
+/// [Arc]s, so that a sender can keep using a value after sending it.
+#[inline]
+pub fn arc_channel() -> (AnyArcSender, AnyArcReceiver)
+{
+    let (tx, rx) = mpsc::channel();
+    (AnyArcSender(tx), AnyArcReceiver(rx))
+}
+
+/// Wraps an [mpsc::Sender] of shared, dynamically typed values.
+#[derive(Debug, Clone)]
+pub struct AnyArcSender(pub Sender<ArcAny>);
+
+impl AnyArcSender
+{
+    /// Wraps [mpsc::Sender::send]. Only the [Arc] is sent; the value itself 
+    /// stays where it is.
+    #[inline]
+    pub fn send<T: Any + Send + Sync>(&self, t: Arc<T>) -> Result<(), mpsc::SendError<ArcAny>>
+    {
+        #[cfg(feature = "type_names")]
+        super::type_names::record::<T>();
+        trace::send::<T>();
+        self.0.send(t)
+    }
+}
+
+/// Wraps an [mpsc::Receiver] of shared, dynamically typed values. A value 
+/// of the wrong type is returned in [AnyRecvError::WrongType] with the 
+/// [TypeId] of the value and, boxed, its `Arc<dyn Any + Send + Sync>`.
+#[derive(Debug)]
+pub struct AnyArcReceiver(pub Receiver<ArcAny>);
+
+impl AnyArcReceiver
+{
+    /// Wraps [mpsc::Receiver::recv].
+    #[inline]
+    pub fn recv<T: Any + Send + Sync>(&self) -> Result<Arc<T>, AnyRecvError>
+    {
+        let result = self.0
+            .recv()
+            .map_err(AnyRecvError::RecvError)
+            .and_then(downcast);
+        trace::recv(&result);
+        result
+    }
+
+    /// Wraps [mpsc::Receiver::recv_timeout].
+    #[inline]
+    pub fn recv_timeout<T: Any + Send + Sync>(&self, timeout: Duration) -> Result<Arc<T>, AnyRecvError>
+    {
+        let result = self.0
+            .recv_timeout(timeout)
+            .map_err(AnyRecvError::RecvTimeoutError)
+            .and_then(downcast);
+        trace::recv(&result);
+        result
+    }
+
+    /// Wraps [mpsc::Receiver::try_recv].
+    #[inline]
+    pub fn try_recv<T: Any + Send + Sync>(&self) -> Result<Arc<T>, AnyRecvError>
+    {
+        let result = self.0
+            .try_recv()
+            .map_err(AnyRecvError::TryRecvError)
+            .and_then(downcast);
+        trace::recv(&result);
+        result
+    }
+}
+
+#[inline]
+fn downcast<T: Any + Send + Sync>(r: ArcAny) -> Result<Arc<T>, AnyRecvError>
+{
+    let type_id = (*r).type_id();
+    r.downcast().map_err(|r| AnyRecvError::WrongType(type_id, Box::new(r)))
+}
@@ -0,0 +1,95 @@
+use super::{mpsc, trace, AnySender, AnyReceiver, AnyRecvError, Wake};
+use core::any::*;
+use futures_core::Stream;
+use std::{pin::Pin, task::{Context, Poll}};
+
+impl AnySender
+{
+    /// Async version of [AnySender::send]. On a bounded channel this waits
+    /// for space without blocking the thread.
+    pub async fn send_async<T: Any>(&self, t: T) -> Result<(), mpsc::SendError<Box<dyn Any>>>
+    {
+        #[cfg(feature = "type_names")]
+        super::type_names::record::<T>();
+        trace::send::<T>();
+        self.0.send_async(Box::new(t)).await
+    }
+
+    /// Converts this sender into a `Sink` of boxed values using `flume`'s
+    /// sink adapter.
+    #[inline]
+    pub fn into_sink(self) -> flume::r#async::SendSink<'static, Box<dyn Any>>
+    {
+        self.0.into_sink()
+    }
+}
+
+impl AnyReceiver
+{
+    /// Async version of [AnyReceiver::recv].
+    pub async fn recv_async<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        let result = self.recv_any_async()
+            .await
+            .and_then(|r| match r.downcast()
+            {
+                Ok(r) => Ok(*r),
+                Err(r) => Err(AnyRecvError::wrong_type(r)),
+            });
+        trace::recv(&result);
+        result
+    }
+
+    /// Async version of [AnyReceiver::recv_any].
+    pub async fn recv_any_async(&self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        let r = match self.1.take()
+        {
+            Some(r) => r,
+            None => loop
+            {
+                let r = self.0.recv_async().await.map_err(AnyRecvError::RecvError)?;
+                if !r.is::<Wake>()
+                {
+                    break r;
+                }
+            }
+        };
+        self.3.copy(r.as_ref());
+        Ok(r)
+    }
+
+    /// Converts this receiver into an [AnyRecvStream] using `flume`'s stream
+    /// adapter. The stream ends once every sender has disconnected.
+    #[inline]
+    pub fn into_stream(self) -> AnyRecvStream
+    {
+        let pending = self.1.take();
+        AnyRecvStream(self.0.into_stream(), pending)
+    }
+}
+
+/// A `Stream` of the boxed values received on an [AnyReceiver].
+#[derive(Debug)]
+pub struct AnyRecvStream(pub flume::r#async::RecvStream<'static, Box<dyn Any>>, Option<Box<dyn Any>>);
+
+impl Stream for AnyRecvStream
+{
+    type Item = Box<dyn Any>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>>
+    {
+        if let Some(r) = self.1.take()
+        {
+            return Poll::Ready(Some(r));
+        }
+        loop
+        {
+            match Pin::new(&mut self.0).poll_next(cx)
+            {
+                Poll::Ready(Some(r)) if r.is::<Wake>() => continue,
+                poll => break poll
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use super::{AnyBuffer, AnyRecvError, BufferedReceiver, RawChannel, ReceiverExt};
+use core::any::*;
+use std::{collections::HashMap, fmt, sync::{Arc, Mutex, MutexGuard, PoisonError, Weak}};
+
+type Handler = Box<dyn FnMut(Box<dyn Any>) + Send>;
+type Subscriber = Arc<Mutex<dyn FnMut(&dyn Any) + Send>>;
+
+/// The handlers a [BufferedReceiver] dispatches received values to.
+#[derive(Default)]
+pub(crate) struct Handlers
+{
+    per_type: HashMap<TypeId, Handler>,
+    fallback: Option<Handler>,
+    subscribers: Arc<Mutex<Subscribers>>
+}
+
+/// Subscribers by type, each list in the order they subscribed.
+#[derive(Default)]
+struct Subscribers
+{
+    next_id: u64,
+    by_type: HashMap<TypeId, Vec<(u64, Subscriber)>>
+}
+
+#[inline]
+fn lock(subscribers: &Mutex<Subscribers>) -> MutexGuard<'_, Subscribers>
+{
+    subscribers.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Handlers
+{
+    /// Shows `r` to each subscriber to its type, then passes it to the
+    /// handler for its type. If there is no handler and no subscriber, `r`
+    /// goes to the fallback, or is handed back if there is none.
+    fn call(&mut self, r: Box<dyn Any>) -> Result<(), Box<dyn Any>>
+    {
+        let type_id = r.as_ref().type_id();
+        let subscribed = self.notify(type_id, r.as_ref());
+        match self.per_type.get_mut(&type_id)
+        {
+            Some(f) => f(r),
+            None if subscribed => (),
+            None => match self.fallback.as_mut()
+            {
+                Some(f) => f(r),
+                None => return Err(r)
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls every subscriber to `type_id` with `t`. The list is copied
+    /// first, so that subscribers may come and go during the calls; one that
+    /// unsubscribes before its turn is skipped. Returns whether there were
+    /// any subscribers.
+    fn notify(&self, type_id: TypeId, t: &dyn Any) -> bool
+    {
+        let subscribers = match lock(&self.subscribers).by_type.get(&type_id)
+        {
+            Some(subscribers) => subscribers.clone(),
+            None => return false
+        };
+        for (id, f) in subscribers
+        {
+            let active = lock(&self.subscribers)
+                .by_type
+                .get(&type_id)
+                .is_some_and(|subscribers| subscribers.iter().any(|(other, _)| *other == id));
+            if active
+            {
+                (f.lock().unwrap_or_else(PoisonError::into_inner))(t);
+            }
+        }
+        true
+    }
+}
+
+impl fmt::Debug for Handlers
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("Handlers")
+            .field("per_type", &self.per_type.len())
+            .field("fallback", &self.fallback.is_some())
+            .field("subscribers", &lock(&self.subscribers).by_type.values().map(Vec::len).sum::<usize>())
+            .finish()
+    }
+}
+
+/// Keeps a handler registered with [BufferedReceiver::subscribe] until it
+/// is dropped.
+#[must_use = "the handler is removed as soon as the Subscription is dropped"]
+pub struct Subscription
+{
+    subscribers: Weak<Mutex<Subscribers>>,
+    type_id: TypeId,
+    id: u64
+}
+
+impl Subscription
+{
+    /// Removes the handler now. The same as dropping the subscription.
+    #[inline]
+    pub fn unsubscribe(self) {}
+}
+
+impl Drop for Subscription
+{
+    fn drop(&mut self)
+    {
+        let Some(subscribers) = self.subscribers.upgrade() else { return };
+        let mut subscribers = lock(&subscribers);
+        if let Some(list) = subscribers.by_type.get_mut(&self.type_id)
+        {
+            list.retain(|(id, _)| *id != self.id);
+            if list.is_empty()
+            {
+                subscribers.by_type.remove(&self.type_id);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Subscription
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("Subscription")
+            .field("type_id", &self.type_id)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl<C: RawChannel, Buf: AnyBuffer> BufferedReceiver<C, Buf>
+{
+    /// Registers `f` to be called with every dispatched value of type `T`,
+    /// replacing any handler previously registered for `T`. See
+    /// [BufferedReceiver::dispatch_one].
+    pub fn on<T: 'static>(&mut self, mut f: impl FnMut(T) + Send + 'static)
+    {
+        self.handlers.per_type.insert(TypeId::of::<T>(), Box::new(move |r: Box<dyn Any>|
+        {
+            f(*r.downcast().expect("handler called with a value of the wrong type"))
+        }));
+    }
+
+    /// Registers `f` to be shown every dispatched value of type `T` for as
+    /// long as the returned [Subscription] is kept. Subscribers to the same
+    /// type are called in the order they subscribed, before the handler
+    /// registered with [BufferedReceiver::on], which still takes the value.
+    /// A value with subscribers but no handler is dropped after they have
+    /// seen it rather than buffered. A subscription may be dropped at any
+    /// time, including by a subscriber during dispatch.
+    pub fn subscribe<T: 'static>(&mut self, mut f: impl FnMut(&T) + Send + 'static) -> Subscription
+    {
+        let type_id = TypeId::of::<T>();
+        let mut subscribers = lock(&self.handlers.subscribers);
+        let id = subscribers.next_id;
+        subscribers.next_id += 1;
+        let f: Subscriber = Arc::new(Mutex::new(move |t: &dyn Any|
+        {
+            f(t.downcast_ref().expect("subscriber called with a value of the wrong type"))
+        }));
+        subscribers.by_type.entry(type_id).or_default().push((id, f));
+        Subscription { subscribers: Arc::downgrade(&self.handlers.subscribers), type_id, id }
+    }
+
+    /// Removes the handler registered for `T`. Returns whether there was one.
+    #[inline]
+    pub fn off<T: 'static>(&mut self) -> bool
+    {
+        self.handlers.per_type.remove(&TypeId::of::<T>()).is_some()
+    }
+
+    /// Registers `f` to be called with every dispatched value whose type has
+    /// no handler, instead of buffering it, replacing any previous fallback.
+    #[inline]
+    pub fn on_unhandled(&mut self, f: impl FnMut(Box<dyn Any>) + Send + 'static)
+    {
+        self.handlers.fallback = Some(Box::new(f));
+    }
+
+    /// Removes the fallback handler, so that values without a handler are
+    /// buffered again. Returns whether there was one.
+    #[inline]
+    pub fn off_unhandled(&mut self) -> bool
+    {
+        self.handlers.fallback.take().is_some()
+    }
+
+    /// Wraps [mpsc::Receiver::recv](std::sync::mpsc::Receiver::recv). Passes
+    /// the received value to the handler registered for its type with
+    /// [BufferedReceiver::on], after showing it to any subscribers added
+    /// with [BufferedReceiver::subscribe]. If there is neither, it goes to the
+    /// [BufferedReceiver::on_unhandled] fallback, or else into the buffer.
+    /// Returns whether a handler or subscriber was called.
+    ///
+    /// Dispatching only receives from the channel: values already in the
+    /// buffer stay there even if a handler is registered for their type
+    /// later. Handlers may be added and removed between dispatches. A
+    /// handler that panics unwinds out of the dispatch call and its value is
+    /// lost, but the receiver and its handlers are left as they were and it
+    /// can go on being used.
+    pub fn dispatch_one(&mut self) -> Result<bool, AnyRecvError>
+    {
+        let result = self.note_received(self.rx.recv_msg()).map_err(AnyRecvError::RecvError);
+        let r = self.record_dyn(result)?;
+        Ok(self.dispatch_dyn(r))
+    }
+
+    /// Dispatches every value waiting on the channel, as
+    /// [BufferedReceiver::dispatch_one] does, without blocking. Returns how
+    /// many were passed to a handler.
+    pub fn dispatch_pending(&mut self) -> usize
+    {
+        let mut count = 0;
+        while let Ok(r) = self.note_received(self.rx.try_recv_msg())
+        {
+            if let Ok(r) = self.record_dyn(Ok(r))
+            {
+                count += usize::from(self.dispatch_dyn(r));
+            }
+        }
+        count
+    }
+
+    /// Dispatches every value, as [BufferedReceiver::dispatch_one] does,
+    /// until every sender has disconnected. Returns how many were passed to
+    /// a handler.
+    pub fn dispatch_blocking_until_disconnect(&mut self) -> usize
+    {
+        let mut count = 0;
+        while let Ok(handled) = self.dispatch_one()
+        {
+            count += usize::from(handled);
+        }
+        count
+    }
+
+    #[inline]
+    fn dispatch_dyn(&mut self, r: Box<dyn Any>) -> bool
+    {
+        match self.handlers.call(r)
+        {
+            Ok(()) => true,
+            Err(r) =>
+            {
+                self.spill(r);
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use super::{AnyBuffer, BufferedReceiver, RawChannel};
+use core::any::*;
+use std::{fmt, marker::PhantomData};
+
+impl<C: RawChannel, Buf: AnyBuffer> BufferedReceiver<C, Buf>
+{
+    /// Iterates over the values of type `T` in the internal buffer, oldest 
+    /// first, without removing them.
+    #[inline]
+    pub fn buf_iter<T: 'static>(&self) -> BufIter<'_, T>
+    {
+        BufIter
+        {
+            values: self.buf.iter_dyn(TypeId::of::<T>()),
+            remaining: self.buf.len_of(TypeId::of::<T>()),
+            _type: PhantomData
+        }
+    }
+
+    /// Removes the values of type `T` from the internal buffer one at a 
+    /// time, oldest first, as [BufferedReceiver::recv_buf] does. Values not
+    /// taken from the iterator stay buffered. Expired values are evicted 
+    /// when the iterator is created and not while it is in use, so its 
+    /// length is exact.
+    #[inline]
+    pub fn buf_drain_iter<T: 'static>(&mut self) -> BufDrainIter<'_, T, C, Buf>
+    {
+        self.evict_expired();
+        BufDrainIter(self, PhantomData)
+    }
+
+    /// Receives values of type `T` one at a time, as 
+    /// [BufferedReceiver::recv_until] does, blocking until each arrives. Ends
+    /// once no `T` is buffered and every sender has disconnected, or on any
+    /// other error.
+    #[inline]
+    pub fn iter_of<T: 'static>(&mut self) -> IterOf<'_, T, C, Buf>
+    {
+        IterOf(self, PhantomData)
+    }
+}
+
+/// Borrows the values of one type in a buffer. Created by 
+/// [BufferedReceiver::buf_iter].
+pub struct BufIter<'a, T>
+{
+    values: Box<dyn Iterator<Item = &'a dyn Any> + 'a>,
+    remaining: usize,
+    _type: PhantomData<&'a T>
+}
+
+impl<'a, T: 'static> Iterator for BufIter<'a, T>
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T>
+    {
+        let t = self.values.next()?;
+        self.remaining -= 1;
+        Some(t.downcast_ref().expect("buffer returned a value of the wrong type"))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: 'static> ExactSizeIterator for BufIter<'_, T> {}
+
+impl<T> fmt::Debug for BufIter<'_, T>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("BufIter")
+            .field("type", &type_name::<T>())
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+/// Removes the values of one type from a buffer. Created by 
+/// [BufferedReceiver::buf_drain_iter].
+pub struct BufDrainIter<'a, T, C: RawChannel, Buf: AnyBuffer>(&'a mut BufferedReceiver<C, Buf>, PhantomData<T>);
+
+impl<T: 'static, C: RawChannel, Buf: AnyBuffer> Iterator for BufDrainIter<'_, T, C, Buf>
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T>
+    {
+        let t = self.0.buf.remove::<T>()?;
+        self.0.record(Ok(t)).ok()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        let remaining = self.0.buf.len_of(TypeId::of::<T>());
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: 'static, C: RawChannel, Buf: AnyBuffer> ExactSizeIterator for BufDrainIter<'_, T, C, Buf> {}
+
+impl<T: 'static, C: RawChannel, Buf: AnyBuffer> fmt::Debug for BufDrainIter<'_, T, C, Buf>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("BufDrainIter")
+            .field("type", &type_name::<T>())
+            .field("remaining", &self.0.buf.len_of(TypeId::of::<T>()))
+            .finish()
+    }
+}
+
+/// Receives the values of one type from a [BufferedReceiver], blocking. 
+/// Created by [BufferedReceiver::iter_of].
+pub struct IterOf<'a, T, C: RawChannel, Buf: AnyBuffer>(&'a mut BufferedReceiver<C, Buf>, PhantomData<T>);
+
+impl<T: 'static, C: RawChannel, Buf: AnyBuffer> Iterator for IterOf<'_, T, C, Buf>
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T>
+    {
+        self.0.recv_until().ok()
+    }
+
+    /// At least the values already buffered; there is no upper bound while 
+    /// senders remain.
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        (self.0.buf.len_of(TypeId::of::<T>()), None)
+    }
+}
+
+impl<T: 'static, C: RawChannel, Buf: AnyBuffer> fmt::Debug for IterOf<'_, T, C, Buf>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("IterOf")
+            .field("type", &type_name::<T>())
+            .field("buffered", &self.0.buf.len_of(TypeId::of::<T>()))
+            .finish()
+    }
+}
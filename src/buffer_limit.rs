@@ -0,0 +1,65 @@
+use super::AnyBuffer;
+use core::any::*;
+
+/// What a [BufferedReceiver](crate::BufferedReceiver) does with a value that
+/// would take its buffer past its [BufferLimit]. Every value dropped is 
+/// counted in 
+/// [BufferedReceiver::dropped_counts](crate::BufferedReceiver::dropped_counts).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OverflowPolicy
+{
+    /// Drops the value that does not fit.
+    #[default]
+    DropNewest,
+    /// Drops the oldest buffered value of the same type to make room, or if 
+    /// there is none, the oldest value of the type [AnyBuffer::find_type] 
+    /// picks first.
+    DropOldest,
+    /// Drops the value that does not fit, and the receive that received it 
+    /// returns [AnyRecvError::BufferFull](crate::AnyRecvError::BufferFull).
+    Error
+}
+
+/// Caps on the number of values a 
+/// [BufferedReceiver](crate::BufferedReceiver) holds in its buffer. The 
+/// default is unbounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BufferLimit
+{
+    /// The most values held across all types.
+    pub total: Option<usize>,
+    /// The most values held of any one type.
+    pub per_type: Option<usize>,
+    pub policy: OverflowPolicy
+}
+
+impl BufferLimit
+{
+    /// A limit of `total` values across all types.
+    #[inline]
+    pub fn total(total: usize, policy: OverflowPolicy) -> Self
+    {
+        BufferLimit { total: Some(total), per_type: None, policy }
+    }
+
+    /// A limit of `per_type` values of each type.
+    #[inline]
+    pub fn per_type(per_type: usize, policy: OverflowPolicy) -> Self
+    {
+        BufferLimit { total: None, per_type: Some(per_type), policy }
+    }
+
+    /// Whether `buf` has no room for another value of type `type_id`.
+    #[inline]
+    pub(crate) fn is_full(&self, buf: &impl AnyBuffer, type_id: TypeId) -> bool
+    {
+        self.total.is_some_and(|total| buf.len() >= total) || self.is_full_of(buf, type_id)
+    }
+
+    /// Whether `buf` holds as many values of type `type_id` as it may.
+    #[inline]
+    pub(crate) fn is_full_of(&self, buf: &impl AnyBuffer, type_id: TypeId) -> bool
+    {
+        self.per_type.is_some_and(|per_type| buf.len_of(type_id) >= per_type)
+    }
+}
@@ -0,0 +1,134 @@
+use super::AnyBuffer;
+use core::any::*;
+use std::{collections::{HashMap, VecDeque}, fmt, time::{Duration, Instant}};
+
+type ExpiryHandler = Box<dyn FnMut(TypeId, Box<dyn Any>) + Send>;
+
+/// How long a [BufferedReceiver](crate::BufferedReceiver) keeps values in
+/// its buffer, and what it does with them once they expire.
+///
+/// Insertion times are kept per type, oldest first, in the same order as the
+/// values of that type in the buffer. Values removed by other means are
+/// assumed to have been the oldest, which can only make the rest look
+/// younger, so a value is never evicted before its time to live has passed.
+#[derive(Default)]
+pub(crate) struct BufferTtl
+{
+    default: Option<Duration>,
+    per_type: HashMap<TypeId, Duration>,
+    stamps: HashMap<TypeId, VecDeque<Instant>>,
+    on_expire: Option<ExpiryHandler>
+}
+
+impl BufferTtl
+{
+    #[inline]
+    pub(crate) fn is_enabled(&self) -> bool
+    {
+        self.default.is_some() || !self.per_type.is_empty()
+    }
+
+    #[inline]
+    pub(crate) fn ttl_of(&self, type_id: TypeId) -> Option<Duration>
+    {
+        self.per_type.get(&type_id).copied().or(self.default)
+    }
+
+    /// Sets the time to live of values of type `type_id`, or of every type
+    /// without its own if `None`. Values already in `buf` that have not been
+    /// timed yet are timed from now.
+    pub(crate) fn set(&mut self, buf: &impl AnyBuffer, type_id: Option<TypeId>, ttl: Duration)
+    {
+        match type_id
+        {
+            Some(type_id) => { self.per_type.insert(type_id, ttl); },
+            None => self.default = Some(ttl)
+        }
+        let now = Instant::now();
+        for type_id in buf.types()
+        {
+            if self.ttl_of(type_id).is_some()
+            {
+                sync(self.stamps.entry(type_id).or_default(), buf.len_of(type_id), now);
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn set_handler(&mut self, f: impl FnMut(TypeId, Box<dyn Any>) + Send + 'static)
+    {
+        self.on_expire = Some(Box::new(f));
+    }
+
+    /// Records that a value of type `type_id` has just been inserted into
+    /// `buf`.
+    pub(crate) fn stamp(&mut self, buf: &impl AnyBuffer, type_id: TypeId)
+    {
+        if self.ttl_of(type_id).is_none()
+        {
+            return;
+        }
+        let now = Instant::now();
+        let stamps = self.stamps.entry(type_id).or_default();
+        sync(stamps, buf.len_of(type_id).saturating_sub(1), now);
+        stamps.push_back(now);
+    }
+
+    /// Removes every expired value from `buf`, passing each to the expiry
+    /// handler if there is one. Returns how many were removed.
+    pub(crate) fn evict(&mut self, buf: &mut impl AnyBuffer) -> usize
+    {
+        if !self.is_enabled()
+        {
+            return 0;
+        }
+        let now = Instant::now();
+        let mut evicted = 0;
+        let BufferTtl { default, per_type, stamps, on_expire } = self;
+        stamps.retain(|type_id, stamps|
+        {
+            let Some(ttl) = per_type.get(type_id).copied().or(*default) else { return false };
+            sync(stamps, buf.len_of(*type_id), now);
+            while stamps.front().is_some_and(|at| now.duration_since(*at) >= ttl)
+            {
+                stamps.pop_front();
+                if let Some(r) = buf.remove_dyn(*type_id)
+                {
+                    evicted += 1;
+                    if let Some(f) = on_expire
+                    {
+                        f(*type_id, r);
+                    }
+                }
+            }
+            !stamps.is_empty()
+        });
+        evicted
+    }
+}
+
+/// Makes `stamps` as long as the number of buffered values it times. Extra
+/// stamps are taken from the front; missing ones are added at the back as
+/// `now`.
+#[inline]
+fn sync(stamps: &mut VecDeque<Instant>, len: usize, now: Instant)
+{
+    if stamps.len() > len
+    {
+        stamps.drain(..stamps.len() - len);
+    }
+    stamps.resize(len, now);
+}
+
+impl fmt::Debug for BufferTtl
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("BufferTtl")
+            .field("default", &self.default)
+            .field("per_type", &self.per_type.len())
+            .field("timed", &self.stamps.values().map(VecDeque::len).sum::<usize>())
+            .field("on_expire", &self.on_expire.is_some())
+            .finish()
+    }
+}
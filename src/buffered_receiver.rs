@@ -1,29 +1,95 @@
-use dfb::*;
-
-use super::{AnySender, AnyRecvError};
+use super::{either, mpsc, trace, type_names, AnyBuffer, AnyReceiver, Casters, Tap, AnySender, BufferLimit, Either, AnyRecvError, BufferTtl, CancelToken, Handlers, OrderedBuffer, OverflowPolicy, RawChannel, ReceiverExt, SharedBufferedReceiver, TypeBuffer};
 use core::any::*;
-use std::sync::mpsc::{self, Receiver};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 /// An [mpsc::channel] that supports dynamic typing and contains a buffer to 
 /// prevent the need for dynamic types to be exposed. 
 #[inline]
 pub fn buffered_channel() -> (AnySender, BufferedReceiver)
+{
+    buffered_channel_with_buffer(TypeBuffer::new())
+}
+
+/// A [buffered_channel] whose buffer has room for values of `capacity` 
+/// distinct types before it reallocates. See [TypeBuffer::with_capacity].
+#[inline]
+pub fn buffered_channel_with_capacity(capacity: usize) -> (AnySender, BufferedReceiver)
+{
+    buffered_channel_with_buffer(TypeBuffer::with_capacity(capacity))
+}
+
+/// A [buffered_channel] whose buffer holds at most `capacity` values. What
+/// happens to a value that does not fit is decided by `policy`; see 
+/// [BufferedReceiver::set_buffer_limit].
+#[inline]
+pub fn buffered_channel_with(capacity: usize, policy: OverflowPolicy) -> (AnySender, BufferedReceiver)
+{
+    let (tx, mut rx) = buffered_channel();
+    rx.set_buffer_limit(BufferLimit::total(capacity, policy));
+    (tx, rx)
+}
+
+#[inline]
+fn buffered_channel_with_buffer(buf: TypeBuffer) -> (AnySender, BufferedReceiver)
 {
     let (tx, rx) = mpsc::channel();
-    (AnySender(tx), BufferedReceiver { rx, buf: Dfb::new() })
+    (AnySender::new(tx), BufferedReceiver::new(rx, buf))
 }
 
 /// Wraps an [mpsc::Receiver] to support dynamic typing and buffered results.
+/// Any other [RawChannel] may be used in its place, and any [AnyBuffer] in 
+/// place of the default [TypeBuffer].
 #[derive(Debug)]
-pub struct BufferedReceiver
+pub struct BufferedReceiver<C: RawChannel = mpsc::Receiver<Box<dyn Any>>, Buf: AnyBuffer = TypeBuffer>
 {
-    pub rx: Receiver<Box<dyn Any>>,
-    pub buf: Dfb
+    pub rx: C,
+    pub buf: Buf,
+    pub(crate) casters: Casters,
+    pub(crate) tap: Tap,
+    limit: BufferLimit,
+    dropped: HashMap<TypeId, u64>,
+    ttl: BufferTtl,
+    pub(crate) handlers: Handlers,
+    #[cfg(feature = "serde")]
+    pub(crate) ser: super::SerRegistry,
+    #[cfg(feature = "metrics")]
+    counts: HashMap<TypeId, u64>,
+    #[cfg(feature = "metrics")]
+    pub(crate) stats: super::StatCounters
 }
-unsafe impl Send for BufferedReceiver {}
+// Only the crate's own channel and buffers are known to hold nothing but 
+// values sent by an AnySender, which is Send on the same terms. A receiver 
+// over any other RawChannel or AnyBuffer is not Send.
+unsafe impl Send for BufferedReceiver<mpsc::Receiver<Box<dyn Any>>, TypeBuffer> {}
+unsafe impl Send for BufferedReceiver<mpsc::Receiver<Box<dyn Any>>, OrderedBuffer> {}
 
-impl BufferedReceiver
+impl<C: RawChannel, Buf: AnyBuffer> BufferedReceiver<C, Buf>
 {
+    /// Wraps `rx`, starting with the values already in `buf`. `rx` should 
+    /// only ever be sent boxed values by an [AnySender].
+    #[inline]
+    pub fn new(rx: C, buf: Buf) -> Self
+    {
+        BufferedReceiver
+        {
+            rx,
+            buf,
+            casters: Casters::default(),
+            tap: Tap::default(),
+            limit: BufferLimit::default(),
+            dropped: HashMap::new(),
+            ttl: BufferTtl::default(),
+            handlers: Handlers::default(),
+            #[cfg(feature = "serde")]
+            ser: super::SerRegistry::new(),
+            #[cfg(feature = "metrics")]
+            counts: HashMap::new(),
+            #[cfg(feature = "metrics")]
+            stats: super::StatCounters::default()
+        }
+    }
+
     /// Wraps [mpsc::Receiver::recv]. See [BufRecvError] for details on the 
     /// return value. Will continuously recv until the supplied type is found. 
     /// Any unmatching types received will be placed in the buffer.
@@ -43,29 +109,146 @@ impl BufferedReceiver
         }
     }
 
+    /// Like [BufferedReceiver::recv_until], but gives up with 
+    /// [AnyRecvError::BufferLimitReached] once it has placed more than 
+    /// `max_buffered` values in the buffer during this call. Everything 
+    /// buffered stays in the buffer. To cap the buffer across every receive
+    /// instead, see [BufferedReceiver::set_buffer_limit].
+    pub fn recv_until_bounded<T: 'static>(&mut self, max_buffered: usize) -> Result<T, AnyRecvError>
+    {
+        let mut buffered = 0;
+        loop
+        {
+            match self.recv::<T>()
+            {
+                Err(AnyRecvError::BufRecvError(_type_id)) =>
+                {
+                    buffered += 1;
+                    if buffered > max_buffered
+                    {
+                        break Err(AnyRecvError::BufferLimitReached(buffered));
+                    }
+                },
+                result => break result
+            }
+        }
+    }
+
+    /// Wraps [mpsc::Receiver::try_recv]. Like [BufferedReceiver::recv_until],
+    /// but never blocks: values waiting on the channel are received, and 
+    /// unmatching ones placed in the buffer, until the supplied type is found
+    /// or the channel is empty or disconnected.
+    pub fn try_recv_until<T: 'static>(&mut self) -> Result<T, AnyRecvError>
+    {
+        loop
+        {
+            match self.try_recv::<T>()
+            {
+                Err(AnyRecvError::BufRecvError(_type_id)) => continue,
+                result => break result
+            }
+        }
+    }
+
+    /// Like [BufferedReceiver::recv_until], but each value of another type
+    /// received while waiting is passed to `on_spill` with its [TypeId] 
+    /// instead of being placed in the buffer. A `T` already in the buffer is
+    /// returned first.
+    pub fn recv_until_with<T: 'static>(&mut self, mut on_spill: impl FnMut(TypeId, Box<dyn Any>)) -> Result<T, AnyRecvError>
+    {
+        if let Some(t) = self.fresh_buf().remove::<T>()
+        {
+            return self.record(Ok(t));
+        }
+        loop
+        {
+            match self.note_received(self.rx.recv_msg())
+            {
+                Ok(r) => match r.downcast()
+                {
+                    Ok(t) => break self.record(Ok(*t)),
+                    Err(r) => on_spill(r.as_ref().type_id(), r)
+                },
+                Err(err) => break self.record(Err(AnyRecvError::RecvError(err)))
+            }
+        }
+    }
+
+    /// Like [BufferedReceiver::recv_until], but gives up with 
+    /// [mpsc::RecvTimeoutError::Timeout] once `timeout` has passed in total.
+    #[inline]
+    pub fn recv_until_timeout<T: 'static>(&mut self, timeout: Duration) -> Result<T, AnyRecvError>
+    {
+        self.recv_until_deadline(Instant::now() + timeout)
+    }
+
+    /// Like [BufferedReceiver::recv_until_timeout], but waits until 
+    /// `deadline` rather than for a duration.
+    pub fn recv_until_deadline<T: 'static>(&mut self, deadline: Instant) -> Result<T, AnyRecvError>
+    {
+        loop
+        {
+            match self.recv_timeout::<T>(deadline.saturating_duration_since(Instant::now()))
+            {
+                // Values that are already waiting are returned even with no 
+                // time left, so a steady stream of other types must not keep
+                // this going past the deadline
+                Err(AnyRecvError::BufRecvError(_type_id)) if Instant::now() >= deadline => 
+                    break Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout)),
+                Err(AnyRecvError::BufRecvError(_type_id)) => continue,
+                result => break result
+            }
+        }
+    }
+
+    /// Like [BufferedReceiver::recv_until], but returns 
+    /// [AnyRecvError::Cancelled] if `token` is cancelled before the supplied
+    /// type is found. Nothing received is lost to a cancellation.
+    pub fn recv_until_cancellable<T: 'static>(&mut self, token: &CancelToken) -> Result<T, AnyRecvError>
+    {
+        if let Some(t) = self.fresh_buf().remove::<T>()
+        {
+            return self.record(Ok(t));
+        }
+        loop
+        {
+            let next = match self.rx.recv_msg_cancellable(token)
+            {
+                Ok(Some(r)) => Ok(r),
+                Ok(None) => break self.record(Err(AnyRecvError::Cancelled)),
+                Err(err) => Err(err)
+            };
+            match self.note_received(next)
+            {
+                Ok(r) => match r.downcast()
+                {
+                    Ok(t) => break self.record(Ok(*t)),
+                    Err(r) => { self.spill(r); }
+                },
+                Err(err) => break self.record(Err(AnyRecvError::RecvError(err)))
+            }
+        }
+    }
+
     /// Wraps [mpsc::Receiver::recv]. See [BufRecvError] for details on the 
     /// return value. Will attempt to take from the internal buffer before
     /// performing an actual channel recv.
     #[inline]
     pub fn recv<T: 'static>(&mut self) -> Result<T, AnyRecvError>
     {
-        match self.buf.remove::<T>()
+        let result = match self.fresh_buf().remove::<T>()
         {
             Some(t) => Ok(t),
-            None => self.rx
-                .recv()
+            None => self
+                .note_received(self.rx.recv_msg())
                 .map_err(AnyRecvError::RecvError)
                 .and_then(|r| match r.downcast()
                 {
                     Ok(r) => Ok(*r),
-                    Err(r) => 
-                    {
-                        let err = Err(AnyRecvError::BufRecvError(r.as_ref().type_id()));
-                        self.buf.insert_dyn(r);
-                        err
-                    },
+                    Err(r) => Err(self.spill(r)),
                 })
-        }
+        };
+        self.record(result)
     }
 
     /// Wraps [mpsc::Receiver::recv]. See [BufRecvError] for details on the 
@@ -74,19 +257,15 @@ impl BufferedReceiver
     #[inline]
     pub fn recv_live<T: 'static>(&mut self) -> Result<T, AnyRecvError>
     {
-        self.rx
-            .recv()
+        let result = self
+            .note_received(self.rx.recv_msg())
             .map_err(AnyRecvError::RecvError)
             .and_then(|r| match r.downcast()
             {
                 Ok(r) => Ok(*r),
-                Err(r) => 
-                {
-                    let err = Err(AnyRecvError::BufRecvError(r.as_ref().type_id()));
-                    self.buf.insert_dyn(r);
-                    err
-                },
-            })
+                Err(r) => Err(self.spill(r)),
+            });
+        self.record(result)
     }
 
     /// Wraps [mpsc::Receiver::recv_timeout]. See [BufRecvError] for 
@@ -95,23 +274,19 @@ impl BufferedReceiver
     #[inline]
     pub fn recv_timeout<T: 'static>(&mut self, timeout: std::time::Duration) -> Result<T, AnyRecvError>
     {
-        match self.buf.remove::<T>()
+        let result = match self.fresh_buf().remove::<T>()
         {
             Some(t) => Ok(t),
-            None => self.rx
-                .recv_timeout(timeout)
+            None => self
+                .note_received(self.rx.recv_timeout_msg(timeout))
                 .map_err(AnyRecvError::RecvTimeoutError)
                 .and_then(|r| match r.downcast()
                 {
                     Ok(r) => Ok(*r),
-                    Err(r) => 
-                    {
-                        let err = Err(AnyRecvError::BufRecvError(r.as_ref().type_id()));
-                        self.buf.insert_dyn(r);
-                        err
-                    }
+                    Err(r) => Err(self.spill(r))
                 })
-        }
+        };
+        self.record(result)
     }
 
     /// Wraps [mpsc::Receiver::recv_timeout]. See [BufRecvError] for 
@@ -120,73 +295,109 @@ impl BufferedReceiver
     #[inline]
     pub fn recv_timeout_live<T: 'static>(&mut self, timeout: std::time::Duration) -> Result<T, AnyRecvError>
     {
-        match self.buf.remove::<T>()
-        {
-            Some(t) => Ok(t),
-            None => self.rx
-                .recv_timeout(timeout)
-                .map_err(AnyRecvError::RecvTimeoutError)
-                .and_then(|r| match r.downcast()
-                {
-                    Ok(r) => Ok(*r),
-                    Err(r) => 
-                    {
-                        let err = Err(AnyRecvError::BufRecvError(r.as_ref().type_id()));
-                        self.buf.insert_dyn(r);
-                        err
-                    }
-                })
-        }
+        let result = self
+            .note_received(self.rx.recv_timeout_msg(timeout))
+            .map_err(AnyRecvError::RecvTimeoutError)
+            .and_then(|r| match r.downcast()
+            {
+                Ok(r) => Ok(*r),
+                Err(r) => Err(self.spill(r)),
+            });
+        self.record(result)
+    }
+
+    /// Like [BufferedReceiver::recv_timeout], but waits until `deadline` 
+    /// rather than for a duration.
+    #[inline]
+    pub fn recv_deadline<T: 'static>(&mut self, deadline: Instant) -> Result<T, AnyRecvError>
+    {
+        self.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Like [BufferedReceiver::recv_timeout_live], but waits until `deadline`
+    /// rather than for a duration.
+    #[inline]
+    pub fn recv_deadline_live<T: 'static>(&mut self, deadline: Instant) -> Result<T, AnyRecvError>
+    {
+        self.recv_timeout_live(deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Wraps [mpsc::Receiver::recv_timeout]. Will continuously recv until the
+    /// supplied type is found, returning `T::default()` if it is not found 
+    /// within `timeout` or the channel is disconnected. Any unmatching types 
+    /// received will be placed in the buffer.
+    #[inline]
+    pub fn recv_timeout_or_default<T: 'static + Default>(&mut self, timeout: Duration) -> T
+    {
+        self.recv_deadline_or_default(Instant::now() + timeout)
+    }
+
+    /// Like [BufferedReceiver::recv_timeout_or_default], but waits until 
+    /// `deadline` rather than for a duration.
+    #[inline]
+    pub fn recv_deadline_or_default<T: 'static + Default>(&mut self, deadline: Instant) -> T
+    {
+        self.recv_until_deadline(deadline).unwrap_or_default()
     }
 
     /// Wraps [mpsc::Receiver::try_recv]. See [BufRecvError] for 
     /// details on the return value. Will attempt to take from the internal 
-    /// buffer before performing an actual channel recv_timeout.
+    /// buffer before performing an actual channel try_recv.
     #[inline]
     pub fn try_recv<T: 'static>(&mut self) -> Result<T, AnyRecvError>
     {
-        match self.buf.remove::<T>()
+        let result = match self.fresh_buf().remove::<T>()
         {
             Some(t) => Ok(t),
-            None => self.rx
-                .try_recv()
+            None => self
+                .note_received(self.rx.try_recv_msg())
                 .map_err(AnyRecvError::TryRecvError)
                 .and_then(|r| match r.downcast()
                 {
                     Ok(r) => Ok(*r),
-                    Err(r) => 
-                    {
-                        let err = Err(AnyRecvError::BufRecvError(r.as_ref().type_id()));
-                        self.buf.insert_dyn(r);
-                        err
-                    }
+                    Err(r) => Err(self.spill(r))
                 })
-        }
+        };
+        self.record(result)
     }
 
     /// Wraps [mpsc::Receiver::try_recv]. See [BufRecvError] for 
-    /// details on the return value. Will perform a channel recv_timeout 
+    /// details on the return value. Will perform a channel try_recv 
     /// regardless of whether or not anything is contained in the buffer.
     #[inline]
     pub fn try_recv_live<T: 'static>(&mut self) -> Result<T, AnyRecvError>
     {
-        match self.buf.remove::<T>()
+        let result = self
+            .note_received(self.rx.try_recv_msg())
+            .map_err(AnyRecvError::TryRecvError)
+            .and_then(|r| match r.downcast()
+            {
+                Ok(r) => Ok(*r),
+                Err(r) => Err(self.spill(r)),
+            });
+        self.record(result)
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Will attempt to take from the internal 
+    /// buffer before performing an actual channel recv, but a received value
+    /// of the wrong type is handed back in a [AnyRecvError::WrongType] rather
+    /// than placed in the buffer, just as [crate::AnyReceiver::recv] does.
+    #[inline]
+    pub fn recv_or_return<T: 'static>(&mut self) -> Result<T, AnyRecvError>
+    {
+        let result = match self.fresh_buf().remove::<T>()
         {
             Some(t) => Ok(t),
-            None => self.rx
-                .try_recv()
-                .map_err(AnyRecvError::TryRecvError)
+            None => self
+                .note_received(self.rx.recv_msg())
+                .map_err(AnyRecvError::RecvError)
                 .and_then(|r| match r.downcast()
                 {
                     Ok(r) => Ok(*r),
-                    Err(r) => 
-                    {
-                        let err = Err(AnyRecvError::BufRecvError(r.as_ref().type_id()));
-                        self.buf.insert_dyn(r);
-                        err
-                    }
+                    Err(r) => Err(AnyRecvError::wrong_type(r)),
                 })
-        }
+        };
+        self.record(result)
     }
 
     /// Wraps [mpsc::Receiver::recv]. See [crate::AnyRecvError] for details on the 
@@ -194,13 +405,13 @@ impl BufferedReceiver
     #[inline]
     pub fn recv_nobuf<T: 'static>(&self) -> Result<T, AnyRecvError>
     {
-        self.rx
-            .recv()
+        self
+            .note_received(self.rx.recv_msg())
             .map_err(AnyRecvError::RecvError)
             .and_then(|r| match r.downcast()
             {
                 Ok(r) => Ok(*r),
-                Err(r) => Err(AnyRecvError::WrongType(r)),
+                Err(r) => Err(AnyRecvError::wrong_type(r)),
             })
     }
 
@@ -209,13 +420,13 @@ impl BufferedReceiver
     #[inline]
     pub fn recv_timeout_nobuf<T: 'static>(&self, timeout: std::time::Duration) -> Result<T, AnyRecvError>
     {
-        self.rx
-            .recv_timeout(timeout)
+        self
+            .note_received(self.rx.recv_timeout_msg(timeout))
             .map_err(AnyRecvError::RecvTimeoutError)
             .and_then(|r| match r.downcast()
             {
                 Ok(r) => Ok(*r),
-                Err(r) => Err(AnyRecvError::WrongType(r)),
+                Err(r) => Err(AnyRecvError::wrong_type(r)),
             })
     }
 
@@ -224,13 +435,13 @@ impl BufferedReceiver
     #[inline]
     pub fn try_recv_nobuf<T: 'static>(&self) -> Result<T, AnyRecvError>
     {
-        self.rx
-            .try_recv()
+        self
+            .note_received(self.rx.try_recv_msg())
             .map_err(AnyRecvError::TryRecvError)
             .and_then(|r| match r.downcast()
             {
                 Ok(r) => Ok(*r),
-                Err(r) => Err(AnyRecvError::WrongType(r)),
+                Err(r) => Err(AnyRecvError::wrong_type(r)),
             })
     }
 
@@ -239,11 +450,1017 @@ impl BufferedReceiver
     #[inline]
     pub fn recv_buf<T: 'static>(&mut self) -> Result<T, AnyRecvError>
     {
-        match self.buf.remove::<T>()
+        let result = match self.fresh_buf().remove::<T>()
         {
             Some(t) => Ok(t),
             None => Err(AnyRecvError::EmptyBuffer)
+        };
+        self.record(result)
+    }
+
+    /// Like [BufferedReceiver::recv_buf], but takes a value of any type: the
+    /// one [BufferedReceiver::recv_any_buf] would take. Returns 
+    /// [AnyRecvError::EmptyBuffer] if the buffer is empty.
+    #[inline]
+    pub fn recv_buf_any(&mut self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        let result = self.fresh_buf()
+            .remove_matching(|_type_id| true)
+            .ok_or(AnyRecvError::EmptyBuffer);
+        self.record_dyn(result)
+    }
+
+    /// Returns a reference to the value [BufferedReceiver::recv_buf] would 
+    /// return next, if any, after evicting expired values as it does. Will 
+    /// not do a channel recv of any kind.
+    #[inline]
+    pub fn peek_buf<T: 'static>(&mut self) -> Option<&T>
+    {
+        self.fresh_buf().peek()
+    }
+
+    /// Like [BufferedReceiver::peek_buf], but the value may be changed in 
+    /// place before it is received.
+    #[inline]
+    pub fn peek_buf_mut<T: 'static>(&mut self) -> Option<&mut T>
+    {
+        self.fresh_buf().peek_mut()
+    }
+
+    /// Removes every value of type `T` from the internal buffer, oldest 
+    /// first. Will not do a channel recv of any kind.
+    pub fn drain_buf<T: 'static>(&mut self) -> Vec<T>
+    {
+        let mut values = Vec::with_capacity(self.buf.len_of(TypeId::of::<T>()));
+        while let Some(t) = self.fresh_buf().remove::<T>()
+        {
+            values.extend(self.record(Ok(t)));
         }
+        values
+    }
+
+    /// Returns the newest `T` in the internal buffer, dropping every older 
+    /// one. If no `T` is buffered, wraps [BufferedReceiver::recv].
+    pub fn recv_latest<T: 'static>(&mut self) -> Result<T, AnyRecvError>
+    {
+        let mut latest = None;
+        while let Some(t) = self.fresh_buf().remove::<T>()
+        {
+            latest = Some(t);
+        }
+        match latest
+        {
+            Some(t) => self.record(Ok(t)),
+            None => self.recv()
+        }
+    }
+
+    /// Removes every value from the internal buffer. Values of the same type
+    /// keep their order; see [AnyBuffer::drain]. Will not do a channel recv 
+    /// of any kind.
+    pub fn drain_buf_all(&mut self) -> Vec<Box<dyn Any>>
+    {
+        self.fresh_buf()
+            .drain()
+            .into_iter()
+            .filter_map(|r| self.record_dyn(Ok(r)).ok())
+            .collect()
+    }
+
+    /// Sends every value in the internal buffer to `target`, in the order 
+    /// [AnyBuffer::drain] returns them, and returns how many were sent. If a
+    /// send fails, returns how many were sent before it along with the 
+    /// error, which holds the value that failed; the rest stay buffered.
+    pub fn flush_to_channel(&mut self, target: &AnySender) -> Result<usize, (usize, mpsc::SendError<Box<dyn Any>>)>
+    {
+        let mut sent = 0;
+        while let Some(r) = self.fresh_buf().remove_matching(|_type_id| true)
+        {
+            if let Ok(r) = self.record_dyn(Ok(r))
+            {
+                target.send_boxed(r).map_err(|e| (sent, e))?;
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Like [BufferedReceiver::flush_to_channel], but only sends the values 
+    /// of type `T`, oldest first. Values of other types stay buffered.
+    pub fn flush_type_to_channel<T: 'static>(&mut self, target: &AnySender) -> Result<usize, (usize, mpsc::SendError<Box<dyn Any>>)>
+    {
+        let mut sent = 0;
+        while let Some(t) = self.fresh_buf().remove::<T>()
+        {
+            if let Ok(t) = self.record(Ok(t))
+            {
+                target.send(t).map_err(|e| (sent, e))?;
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Removes every buffered `T` and sends it via `tx`, oldest first, 
+    /// returning how many were forwarded, e.g. to reroute them to another
+    /// receiver. Stops at the first failed send, whose error holds the value
+    /// that failed; the rest stay buffered. Use 
+    /// [BufferedReceiver::flush_type_to_channel] to also learn how many were
+    /// forwarded before the failure.
+    #[inline]
+    pub fn forward_buffered<T: 'static>(&mut self, tx: &AnySender) -> Result<usize, mpsc::SendError<Box<dyn Any>>>
+    {
+        self.flush_type_to_channel::<T>(tx).map_err(|(_sent, e)| e)
+    }
+
+    /// Drops every value in the internal buffer.
+    #[inline]
+    pub fn clear_buf(&mut self)
+    {
+        self.buf.clear();
+    }
+
+    /// Drops every value of type `T` in the internal buffer, returning how 
+    /// many were dropped.
+    #[inline]
+    pub fn clear_buf_of<T: 'static>(&mut self) -> usize
+    {
+        self.buf.clear_of(TypeId::of::<T>())
+    }
+
+    /// Keeps only the values in the internal buffer that pass `pred`, which
+    /// is given each value and its [TypeId]. The rest are dropped.
+    #[inline]
+    pub fn retain_buf(&mut self, mut pred: impl FnMut(TypeId, &dyn Any) -> bool)
+    {
+        self.fresh_buf().retain(&mut pred);
+    }
+
+    /// Wraps [mpsc::Receiver::try_recv]. Returns every value of type `T` 
+    /// available without blocking: those in the internal buffer, then those 
+    /// waiting on the channel, in the order they were sent. Any unmatching 
+    /// types received will be placed in the buffer.
+    pub fn recv_all<T: 'static>(&mut self) -> Vec<T>
+    {
+        let mut values = self.drain_buf::<T>();
+        while let Ok(r) = self.note_received(self.rx.try_recv_msg())
+        {
+            match r.downcast()
+            {
+                Ok(t) => values.extend(self.record(Ok(*t))),
+                Err(r) => { self.spill(r); }
+            }
+        }
+        values
+    }
+
+    /// Wraps [mpsc::Receiver::try_recv]. Appends every value available 
+    /// without blocking to `out`: those in the internal buffer, in the order
+    /// [AnyBuffer::drain] returns them, then those waiting on the channel, in
+    /// the order they were sent. Returns how many were appended. Reusing 
+    /// `out` avoids allocating on every call.
+    pub fn recv_all_into(&mut self, out: &mut Vec<Box<dyn Any>>) -> usize
+    {
+        let before = out.len();
+        for r in self.fresh_buf().drain()
+        {
+            out.extend(self.record_dyn(Ok(r)));
+        }
+        while let Ok(r) = self.note_received(self.rx.try_recv_msg())
+        {
+            out.extend(self.record_dyn(Ok(r)));
+        }
+        out.len() - before
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Receives a value whose type passes
+    /// `pred`, checking the internal buffer before performing an actual
+    /// channel recv. A received value that does not pass `pred` will be
+    /// placed in the buffer.
+    #[inline]
+    pub fn recv_filter(&mut self, pred: impl Fn(TypeId) -> bool) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        let result = match self.fresh_buf().remove_matching(&pred)
+        {
+            Some(r) => Ok(r),
+            None => self
+                .note_received(self.rx.recv_msg())
+                .map_err(AnyRecvError::RecvError)
+                .and_then(|r| match pred(r.as_ref().type_id())
+                {
+                    true => Ok(r),
+                    false => Err(self.spill(r))
+                })
+        };
+        self.record_dyn(result)
+    }
+
+    /// Wraps [mpsc::Receiver::try_recv]. Receives a value whose type passes
+    /// `pred`, checking the internal buffer before performing an actual
+    /// channel try_recv. A received value that does not pass `pred` will be
+    /// placed in the buffer.
+    #[inline]
+    pub fn try_recv_filter(&mut self, pred: impl Fn(TypeId) -> bool) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        let result = match self.fresh_buf().remove_matching(&pred)
+        {
+            Some(r) => Ok(r),
+            None => self
+                .note_received(self.rx.try_recv_msg())
+                .map_err(AnyRecvError::TryRecvError)
+                .and_then(|r| match pred(r.as_ref().type_id())
+                {
+                    true => Ok(r),
+                    false => Err(self.spill(r))
+                })
+        };
+        self.record_dyn(result)
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Returns the received value without
+    /// attempting to downcast it. Will take a value from the internal buffer,
+    /// if there is one, before performing an actual channel recv.
+    ///
+    /// Every buffered value arrived before anything still on the channel, so
+    /// the buffer is always emptied first. Values of the same type come out 
+    /// in the order they were sent. Across types, buffered values come out in
+    /// the order [AnyBuffer::find_type] gives: any order for [TypeBuffer], 
+    /// and send order for [OrderedBuffer](crate::OrderedBuffer), which makes
+    /// the whole sequence match the order values were sent in.
+    #[inline]
+    pub fn recv_any(&mut self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        self.recv_filter(|_type_id| true)
+    }
+
+    /// Wraps [mpsc::Receiver::try_recv]. Returns the received value without
+    /// attempting to downcast it. Will take a value from the internal buffer,
+    /// if there is one, before performing an actual channel try_recv. Values
+    /// come out in the order described for [BufferedReceiver::recv_any].
+    #[inline]
+    pub fn try_recv_any(&mut self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        self.try_recv_filter(|_type_id| true)
+    }
+
+    /// Wraps [mpsc::Receiver::recv_timeout]. Returns the received value 
+    /// without attempting to downcast it. Will take a value from the internal
+    /// buffer, if there is one, before performing an actual channel 
+    /// recv_timeout. Values come out in the order described for 
+    /// [BufferedReceiver::recv_any].
+    #[inline]
+    pub fn recv_timeout_any(&mut self, timeout: Duration) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        let result = match self.fresh_buf().remove_matching(|_type_id| true)
+        {
+            Some(r) => Ok(r),
+            None => self
+                .note_received(self.rx.recv_timeout_msg(timeout))
+                .map_err(AnyRecvError::RecvTimeoutError)
+        };
+        self.record_dyn(result)
+    }
+
+    /// Removes and returns a value from the internal buffer without 
+    /// specifying its type, or `None` if the buffer is empty. Which value 
+    /// comes out first follows [TypeBuffer::remove_matching]. Will not do a 
+    /// channel recv of any kind.
+    #[inline]
+    pub fn recv_any_buf(&mut self) -> Option<Box<dyn Any>>
+    {
+        let r = self.fresh_buf().remove_matching(|_type_id| true)?;
+        self.record_dyn(Ok(r)).ok()
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Receives a value of type `A` or `B`,
+    /// checking the internal buffer for either before performing an actual 
+    /// channel recv. A received value of any other type will be placed in the
+    /// buffer.
+    #[inline]
+    pub fn recv_either<A: 'static, B: 'static>(&mut self) -> Result<Either<A, B>, AnyRecvError>
+    {
+        self.recv_filter(either::is_either::<A, B>)
+            .map(either::downcast_either)
+    }
+
+    /// Sends a copy of every value this receiver hands out to `tx`, 
+    /// replacing any previous tap. Values placed in the buffer are copied 
+    /// once they are eventually handed out. See [AnyReceiver::tap].
+    #[inline]
+    pub fn tap(&mut self, tx: AnySender)
+    {
+        self.tap.set(tx);
+    }
+
+    /// Allows values of type `T` to be copied to the tap by cloning them. See
+    /// [BufferedReceiver::tap].
+    #[inline]
+    pub fn register_tap_clone<T: Clone + 'static>(&mut self)
+    {
+        self.tap.register_clone::<T>();
+    }
+
+    /// Allows values of type `T` to be received as `U`, usually a trait
+    /// object, by [BufferedReceiver::recv_dyn]. See 
+    /// [AnyReceiver::register_caster].
+    #[inline]
+    pub fn register_caster<T: 'static, U: ?Sized + 'static>(&mut self, f: impl Fn(Box<T>) -> Box<U> + Send + 'static)
+    {
+        self.casters.register(f);
     }
-}
 
+    /// Wraps [mpsc::Receiver::recv]. Receives a value of any type with a 
+    /// caster to `U` registered, taking from the buffer first, and returns it
+    /// cast. A value of any other type is placed in the buffer and a 
+    /// [BufRecvError] returned.
+    ///
+    /// ```
+    /// # use any_mpsc::register_casters;
+    /// trait Shape { fn area(&self) -> f64; }
+    /// struct Square(f64);
+    /// struct Circle(f64);
+    /// impl Shape for Square { fn area(&self) -> f64 { self.0 * self.0 } }
+    /// impl Shape for Circle { fn area(&self) -> f64 { 3.0 * self.0 * self.0 } }
+    ///
+    /// let (tx, mut rx) = any_mpsc::buffered_channel();
+    /// register_casters!(rx, dyn Shape => Square, Circle);
+    /// tx.send(Square(2.0)).unwrap();
+    /// tx.send(Circle(1.0)).unwrap();
+    /// let total: f64 = (0..2).map(|_| rx.recv_dyn::<dyn Shape>().unwrap().area()).sum();
+    /// assert_eq!(total, 7.0);
+    /// ```
+    pub fn recv_dyn<U: ?Sized + 'static>(&mut self) -> Result<Box<U>, AnyRecvError>
+    {
+        self.evict_expired();
+        let casters = &self.casters;
+        let r = match self.buf.remove_matching(|type_id| casters.has::<U>(type_id))
+        {
+            Some(r) => r,
+            None => self.note_received(self.rx.recv_msg()).map_err(AnyRecvError::RecvError)?
+        };
+        if !self.casters.has::<U>(r.as_ref().type_id())
+        {
+            return Err(self.spill(r));
+        }
+        let r = self.record_dyn(Ok(r))?;
+        Ok(self.casters.cast(r).expect("caster was checked to be registered"))
+    }
+
+    /// Receives a value of any type registered for `Tr` with 
+    /// [register_trait](crate::register_trait) as a `Box<Tr>`. The same as
+    /// [BufferedReceiver::recv_dyn].
+    #[inline]
+    pub fn recv_as<Tr: ?Sized + 'static>(&mut self) -> Result<Box<Tr>, AnyRecvError>
+    {
+        self.recv_dyn()
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Will continuously recv until a value
+    /// whose type passes `pred` is found. Any values that do not pass will be
+    /// placed in the buffer.
+    pub fn recv_filter_until(&mut self, pred: impl Fn(TypeId) -> bool) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        loop
+        {
+            match self.recv_filter(&pred)
+            {
+                Err(AnyRecvError::BufRecvError(_type_id)) => continue,
+                result => break result
+            }
+        }
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Will continuously recv until a value
+    /// that passes `pred` is found, checking the internal buffer first. 
+    /// Unlike [BufferedReceiver::recv_filter_until], `pred` sees the value 
+    /// itself, so it can accept types only known at runtime or inspect 
+    /// contents. Any values that do not pass will be placed in the buffer.
+    pub fn recv_where(&mut self, pred: impl Fn(&dyn Any) -> bool) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        if let Some(r) = self.fresh_buf().remove_any_where(&pred)
+        {
+            return self.record_dyn(Ok(r));
+        }
+        loop
+        {
+            match self.note_received(self.rx.recv_msg())
+            {
+                Ok(r) if pred(r.as_ref()) => break self.record_dyn(Ok(r)),
+                Ok(r) => { self.spill(r); },
+                Err(err) => break Err(AnyRecvError::RecvError(err))
+            }
+        }
+    }
+
+    /// Wraps [mpsc::Receiver::recv_timeout]. Will continuously recv until `n`
+    /// values of type `T` have been found, checking the internal buffer 
+    /// first. Any unmatching types received will be placed in the buffer. 
+    /// `timeout` applies to the whole batch rather than to each value. On 
+    /// failure the values collected so far are returned alongside the error.
+    #[inline]
+    pub fn recv_n<T: 'static>(&mut self, n: usize, timeout: Duration) -> Result<Vec<T>, (Vec<T>, AnyRecvError)>
+    {
+        self.recv_repeated_deadline(n, Instant::now() + timeout)
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Receives `n` values of type `T` in the 
+    /// order they were sent, as [BufferedReceiver::recv_until] would one at a
+    /// time. On failure the values collected so far are returned alongside 
+    /// the error.
+    #[inline]
+    pub fn recv_repeated<T: 'static>(&mut self, n: usize) -> Result<Vec<T>, (Vec<T>, AnyRecvError)>
+    {
+        self.recv_repeated_with(n, Self::recv_until)
+    }
+
+    /// Like [BufferedReceiver::recv_repeated], but gives up if any one value
+    /// takes longer than `timeout` to arrive.
+    #[inline]
+    pub fn recv_repeated_timeout<T: 'static>(&mut self, n: usize, timeout: Duration) -> Result<Vec<T>, (Vec<T>, AnyRecvError)>
+    {
+        self.recv_repeated_with(n, |rx| rx.recv_until_timeout(timeout))
+    }
+
+    /// Like [BufferedReceiver::recv_repeated], but gives up once `deadline` 
+    /// passes. See also [BufferedReceiver::recv_n].
+    #[inline]
+    pub fn recv_repeated_deadline<T: 'static>(&mut self, n: usize, deadline: Instant) -> Result<Vec<T>, (Vec<T>, AnyRecvError)>
+    {
+        self.recv_repeated_with(n, |rx| rx.recv_until_deadline(deadline))
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Receives values of type `T` for as long 
+    /// as they pass `pred`, checking the internal buffer first. Any unmatching
+    /// types received will be placed in the buffer. Stops at the first `T` 
+    /// that does not pass, which is left in the buffer as the next `T` to be
+    /// received, or once the channel disconnects.
+    #[inline]
+    pub fn recv_while<T: 'static>(&mut self, pred: impl FnMut(&T) -> bool) -> Vec<T>
+    {
+        self.recv_while_with(pred, |rx| rx.recv_msg())
+    }
+
+    /// Like [BufferedReceiver::recv_while], but never blocks: also stops once
+    /// the channel is empty.
+    #[inline]
+    pub fn try_recv_while<T: 'static>(&mut self, pred: impl FnMut(&T) -> bool) -> Vec<T>
+    {
+        self.recv_while_with(pred, |rx| rx.try_recv_msg())
+    }
+
+    /// Like [BufferedReceiver::recv_while], but also stops once `timeout` has
+    /// passed. `timeout` applies to the whole run rather than to each value.
+    #[inline]
+    pub fn recv_while_timeout<T: 'static>(&mut self, pred: impl FnMut(&T) -> bool, timeout: Duration) -> Vec<T>
+    {
+        let deadline = Instant::now() + timeout;
+        self.recv_while_with(pred, |rx| rx.recv_timeout_msg(deadline.saturating_duration_since(Instant::now())))
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Will continuously recv until a value of 
+    /// type `T` is found, checking the internal buffer first, and calls `f` 
+    /// with it. Any unmatching types received will be placed in the buffer. 
+    /// The value itself is left in the buffer, so the next `T` received is 
+    /// the one `f` inspected.
+    #[inline]
+    pub fn recv_inspect<T: 'static, R>(&mut self, f: impl FnOnce(&T) -> R) -> Result<R, AnyRecvError>
+    {
+        self.recv_inspect_with(f, |rx| rx.recv_msg().map_err(AnyRecvError::RecvError))
+    }
+
+    /// Like [BufferedReceiver::recv_inspect], but gives up once `timeout` has
+    /// passed. `timeout` applies to the whole search rather than to each 
+    /// value received.
+    #[inline]
+    pub fn recv_timeout_inspect<T: 'static, R>(&mut self, f: impl FnOnce(&T) -> R, timeout: Duration) -> Result<R, AnyRecvError>
+    {
+        let deadline = Instant::now() + timeout;
+        self.recv_inspect_with(f, |rx| rx
+            .recv_timeout_msg(deadline.saturating_duration_since(Instant::now()))
+            .map_err(AnyRecvError::RecvTimeoutError))
+    }
+
+    /// Like [BufferedReceiver::recv_inspect], but never blocks: gives up once
+    /// the channel is empty.
+    #[inline]
+    pub fn try_recv_inspect<T: 'static, R>(&mut self, f: impl FnOnce(&T) -> R) -> Result<R, AnyRecvError>
+    {
+        self.recv_inspect_with(f, |rx| rx.try_recv_msg().map_err(AnyRecvError::TryRecvError))
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Will continuously recv until a value of
+    /// type `T` that passes `pred` is found, checking the internal buffer 
+    /// first. Values of type `T` that do not pass are placed in the buffer 
+    /// rather than discarded. A value of any other type is placed in the 
+    /// buffer and returned as [AnyRecvError::BufRecvError], as with 
+    /// [BufferedReceiver::recv].
+    pub fn filter_recv<T: 'static>(&mut self, pred: impl Fn(&T) -> bool) -> Result<T, AnyRecvError>
+    {
+        if let Some(t) = self.fresh_buf().remove_where(&pred)
+        {
+            return self.record(Ok(t));
+        }
+        loop
+        {
+            let r = self.note_received(self.rx.recv_msg()).map_err(AnyRecvError::RecvError)?;
+            match r.downcast_ref()
+            {
+                Some(t) if pred(t) => break self.record(Ok(*r.downcast().expect("value was checked to be a T"))),
+                Some(_) => self.push_back(r),
+                None => break Err(self.spill(r))
+            }
+        }
+    }
+
+    /// Moves every value currently waiting on the channel into the buffer
+    /// without blocking. Stops once the channel is empty or disconnected and
+    /// returns the number of values moved.
+    pub fn flush_channel_to_buffer(&mut self) -> usize
+    {
+        let mut count = 0;
+        while let Ok(r) = self.note_received(self.rx.try_recv_msg())
+        {
+            self.spill(r);
+            count += 1;
+        }
+        count
+    }
+
+    /// Consumes the receiver and returns everything it holds: the buffer 
+    /// contents first, then every value still waiting on the channel. Values
+    /// taken from the channel keep the order they were sent in; see 
+    /// [TypeBuffer::drain] for the order of buffered values. Never blocks.
+    pub fn drain(mut self) -> Vec<Box<dyn Any>>
+    {
+        let mut values = self.fresh_buf().drain();
+        while let Ok(r) = self.note_received(self.rx.try_recv_msg())
+        {
+            values.push(r);
+        }
+        values
+    }
+
+    /// Like [BufferedReceiver::drain], but separates the values of type `T` 
+    /// from the rest.
+    #[inline]
+    pub fn drain_of<T: 'static>(self) -> (Vec<T>, Vec<Box<dyn Any>>)
+    {
+        super::partition(self.drain())
+    }
+
+    /// Reserves room in the buffer for values of at least `additional` more 
+    /// distinct types. See [TypeBuffer::reserve].
+    #[inline]
+    pub fn reserve(&mut self, additional: usize)
+    {
+        self.buf.reserve(additional)
+    }
+
+    /// Whether a value of type `T` is held in the buffer. Never touches the
+    /// channel.
+    #[inline]
+    pub fn buffer_has<T: 'static>(&self) -> bool
+    {
+        self.buf.contains(TypeId::of::<T>())
+    }
+
+    /// If a value is waiting, returns the [TypeId] of the value 
+    /// [BufferedReceiver::recv_any] would return next, without blocking. 
+    /// When the buffer is empty, a value waiting on the channel is moved into 
+    /// the buffer to be inspected, which never loses a value or reorders 
+    /// values of the same type.
+    pub fn ready(&mut self) -> Option<TypeId>
+    {
+        if let Some(type_id) = self.fresh_buf().find_type(&|_type_id| true)
+        {
+            return Some(type_id);
+        }
+        let r = self.note_received(self.rx.try_recv_msg()).ok()?;
+        let type_id = r.as_ref().type_id();
+        self.push_back(r);
+        Some(type_id)
+    }
+
+    /// Whether a value of type `T` is waiting, either in the buffer or next 
+    /// on the channel. Like [BufferedReceiver::ready], may move one value 
+    /// from the channel into the buffer.
+    pub fn ready_is<T: 'static>(&mut self) -> bool
+    {
+        if self.buffer_has::<T>()
+        {
+            return true;
+        }
+        match self.note_received(self.rx.try_recv_msg())
+        {
+            Ok(r) =>
+            {
+                let is_t = r.is::<T>();
+                self.push_back(r);
+                is_t
+            },
+            Err(_) => false
+        }
+    }
+
+    /// Number of values currently held in the buffer.
+    #[inline]
+    pub fn buffer_len(&self) -> usize
+    {
+        self.buf.len()
+    }
+
+    /// Number of values of type `T` currently held in the buffer.
+    #[inline]
+    pub fn buffer_len_of<T: 'static>(&self) -> usize
+    {
+        self.buf.len_of(TypeId::of::<T>())
+    }
+
+    /// The distinct types of the values currently held in the buffer.
+    #[inline]
+    pub fn buffer_types(&self) -> Vec<TypeId>
+    {
+        self.buf.types()
+    }
+
+    /// The names of the types returned by [BufferedReceiver::buffer_types], 
+    /// in the same order. The receiver only ever sees values boxed, so a 
+    /// name is `None` unless the type was registered with 
+    /// [register_type_name](crate::register_type_name) or sent with the 
+    /// `type_names` feature; see [AnyRecvError::received_type_name].
+    pub fn buffer_type_names(&self) -> Vec<Option<&'static str>>
+    {
+        self.buf
+            .types()
+            .into_iter()
+            .map(type_names::lookup)
+            .collect()
+    }
+
+    /// The type name of every buffered value, grouped by type in the order
+    /// of [BufferedReceiver::buffer_types], for diagnostics. A name is only 
+    /// known if its type was registered with 
+    /// [register_type_name](crate::register_type_name), or sent by 
+    /// [AnySender::send] with the `type_names` feature enabled; otherwise it
+    /// is given as `"<unknown>"`.
+    pub fn buffered_type_names(&self) -> Vec<&'static str>
+    {
+        self.buf
+            .types()
+            .into_iter()
+            .flat_map(|type_id|
+            {
+                let name = type_names::lookup(type_id).unwrap_or("<unknown>");
+                vec![name; self.buf.len_of(type_id)]
+            })
+            .collect()
+    }
+
+    /// The limit on the values held in the buffer. Unbounded unless set by 
+    /// [BufferedReceiver::set_buffer_limit] or [buffered_channel_with].
+    #[inline]
+    pub fn buffer_limit(&self) -> BufferLimit
+    {
+        self.limit
+    }
+
+    /// Limits the values held in the buffer. The limit is applied by every 
+    /// receive that buffers a value of a type other than the one asked for;
+    /// values already buffered past it are kept. A value of the type asked 
+    /// for that is put back, such as one probed by [BufferedReceiver::ready]
+    /// or one failing the predicate of [BufferedReceiver::filter_recv], is 
+    /// always kept, even past the limit.
+    #[inline]
+    pub fn set_buffer_limit(&mut self, limit: BufferLimit)
+    {
+        self.limit = limit;
+    }
+
+    /// Number of values dropped so far because the buffer was full, per type.
+    #[inline]
+    pub fn dropped_counts(&self) -> &HashMap<TypeId, u64>
+    {
+        &self.dropped
+    }
+
+    /// Number of values dropped so far because the buffer was full.
+    #[inline]
+    pub fn dropped_count(&self) -> u64
+    {
+        self.dropped.values().sum()
+    }
+
+    /// Evicts values from the buffer once they have been held for `ttl`. 
+    /// Values already buffered are timed from now. Eviction happens on the 
+    /// next receive or other change to the buffer, or on 
+    /// [BufferedReceiver::evict_expired]; methods that only borrow the 
+    /// receiver, such as [BufferedReceiver::buffer_len], still see values 
+    /// that have expired but not yet been evicted. Evicted values are dropped
+    /// unless [BufferedReceiver::on_expire] or [BufferedReceiver::expire_to] 
+    /// is set.
+    #[inline]
+    pub fn set_buffer_ttl(&mut self, ttl: Duration)
+    {
+        self.ttl.set(&self.buf, None, ttl);
+    }
+
+    /// Like [BufferedReceiver::set_buffer_ttl], but only for values of type 
+    /// `T`, overriding the TTL set for every type.
+    #[inline]
+    pub fn set_buffer_ttl_of<T: 'static>(&mut self, ttl: Duration)
+    {
+        self.ttl.set(&self.buf, Some(TypeId::of::<T>()), ttl);
+    }
+
+    /// The TTL of buffered values of type `T`, if any. See 
+    /// [BufferedReceiver::set_buffer_ttl].
+    #[inline]
+    pub fn buffer_ttl_of<T: 'static>(&self) -> Option<Duration>
+    {
+        self.ttl.ttl_of(TypeId::of::<T>())
+    }
+
+    /// Passes every value evicted by the buffer TTL to `f` with its 
+    /// [TypeId], replacing any previous handler or [BufferedReceiver::expire_to].
+    #[inline]
+    pub fn on_expire(&mut self, f: impl FnMut(TypeId, Box<dyn Any>) + Send + 'static)
+    {
+        self.ttl.set_handler(f);
+    }
+
+    /// Sends every value evicted by the buffer TTL to `tx`, as a dead letter
+    /// channel, replacing any previous [BufferedReceiver::on_expire] handler. 
+    /// Values that cannot be sent are dropped.
+    #[inline]
+    pub fn expire_to(&mut self, tx: AnySender)
+    {
+        self.ttl.set_handler(move |_type_id, r|
+        {
+            let _ = tx.send_boxed(r);
+        });
+    }
+
+    /// Evicts every buffered value that has outlived its TTL now, rather 
+    /// than on the next change to the buffer. Returns how many were evicted.
+    /// See [BufferedReceiver::set_buffer_ttl].
+    #[inline]
+    pub fn evict_expired(&mut self) -> usize
+    {
+        self.ttl.evict(&mut self.buf)
+    }
+
+    /// Number of values successfully received so far, per type. Counts every
+    /// receive that returns a value, whether it came from the buffer or the
+    /// channel, except those made through the `_nobuf` methods.
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn recv_counts(&self) -> &HashMap<TypeId, u64>
+    {
+        &self.counts
+    }
+
+    /// Records the result of a typed receive in 
+    /// [BufferedReceiver::recv_counts], traces it and copies it to the tap.
+    #[inline]
+    pub(crate) fn record<T: 'static>(&mut self, result: Result<T, AnyRecvError>) -> Result<T, AnyRecvError>
+    {
+        if let Ok(t) = &result
+        {
+            #[cfg(feature = "metrics")]
+            {
+                *self.counts.entry(TypeId::of::<T>()).or_insert(0) += 1;
+            }
+            self.tap.copy(t);
+        }
+        trace::recv(&result);
+        result
+    }
+
+    /// Records the result of an untyped receive in 
+    /// [BufferedReceiver::recv_counts], traces it and copies it to the tap.
+    #[inline]
+    pub(crate) fn record_dyn(&mut self, result: Result<Box<dyn Any>, AnyRecvError>) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        if let Ok(r) = &result
+        {
+            #[cfg(feature = "metrics")]
+            {
+                *self.counts.entry(r.as_ref().type_id()).or_insert(0) += 1;
+            }
+            self.tap.copy(r.as_ref());
+        }
+        trace::recv_any(&result);
+        result
+    }
+
+    /// Counts a value taken off the channel in [BufferedReceiver::stats].
+    #[inline]
+    pub(crate) fn note_received<E>(&self, result: Result<Box<dyn Any>, E>) -> Result<Box<dyn Any>, E>
+    {
+        #[cfg(feature = "metrics")]
+        if result.is_ok()
+        {
+            self.stats.count_received();
+        }
+        result
+    }
+
+    /// Shared implementation of the `recv_repeated` methods. `next` receives
+    /// a single value.
+    fn recv_repeated_with<T: 'static>(
+        &mut self, 
+        n: usize, 
+        mut next: impl FnMut(&mut Self) -> Result<T, AnyRecvError>) -> Result<Vec<T>, (Vec<T>, AnyRecvError)>
+    {
+        let mut values = Vec::with_capacity(n);
+        while values.len() < n
+        {
+            match next(self)
+            {
+                Ok(t) => values.push(t),
+                Err(err) => return Err((values, err))
+            }
+        }
+        Ok(values)
+    }
+
+    /// Shared implementation of the `recv_inspect` methods. `next` takes a 
+    /// value off the channel.
+    fn recv_inspect_with<T: 'static, R>(
+        &mut self, 
+        f: impl FnOnce(&T) -> R, 
+        mut next: impl FnMut(&C) -> Result<Box<dyn Any>, AnyRecvError>) -> Result<R, AnyRecvError>
+    {
+        loop
+        {
+            if let Some(t) = self.fresh_buf().peek::<T>()
+            {
+                break Ok(f(t));
+            }
+            // A T received here becomes the only buffered T, so it is found 
+            // on the next pass
+            let r = self.note_received(next(&self.rx))?;
+            match r.is::<T>()
+            {
+                true => self.push_back(r),
+                false => { self.spill(r); }
+            }
+        }
+    }
+
+    /// Shared implementation of the `recv_while` methods. `next` takes a value
+    /// off the channel, or returns an error to end the run.
+    fn recv_while_with<T: 'static, E>(
+        &mut self, 
+        mut pred: impl FnMut(&T) -> bool, 
+        mut next: impl FnMut(&C) -> Result<Box<dyn Any>, E>) -> Vec<T>
+    {
+        let mut values = Vec::new();
+        loop
+        {
+            let t = match self.fresh_buf().peek::<T>()
+            {
+                Some(t) if pred(t) => self.fresh_buf().remove::<T>().expect("peeked value is still buffered"),
+                Some(_) => break,
+                None => match self.note_received(next(&self.rx))
+                {
+                    Ok(r) => match r.downcast_ref::<T>().map(&mut pred)
+                    {
+                        Some(true) => *r.downcast().expect("value was checked to be a T"),
+                        // The failing value is now the only buffered T
+                        Some(false) => { self.push_back(r); break; },
+                        None => { self.spill(r); continue; }
+                    },
+                    Err(_) => break
+                }
+            };
+            if let Ok(t) = self.record(Ok(t))
+            {
+                values.push(t);
+            }
+        }
+        values
+    }
+
+    /// The buffer, after evicting any values that have outlived their TTL.
+    #[inline]
+    fn fresh_buf(&mut self) -> &mut Buf
+    {
+        self.ttl.evict(&mut self.buf);
+        &mut self.buf
+    }
+
+    /// Places a received value of a type that was not asked for in the 
+    /// buffer, or drops it if the [BufferLimit] leaves no room.
+    pub(crate) fn spill(&mut self, r: Box<dyn Any>) -> AnyRecvError
+    {
+        let type_id = r.as_ref().type_id();
+        self.evict_expired();
+        if self.limit.is_full(&self.buf, type_id)
+        {
+            let room = self.limit.policy == OverflowPolicy::DropOldest && self.make_room(type_id);
+            if !room
+            {
+                *self.dropped.entry(type_id).or_insert(0) += 1;
+                return match self.limit.policy
+                {
+                    OverflowPolicy::Error => AnyRecvError::BufferFull(type_id),
+                    _ => AnyRecvError::BufRecvError(type_id)
+                };
+            }
+        }
+        self.push_back(r);
+        AnyRecvError::BufRecvError(type_id)
+    }
+
+    /// Places a value the caller was working with, such as one that was only
+    /// probed or failed a predicate, in the buffer. Unlike 
+    /// [BufferedReceiver::spill] this ignores the [BufferLimit], since 
+    /// dropping it would lose a value the caller was promised.
+    fn push_back(&mut self, r: Box<dyn Any>)
+    {
+        let type_id = r.as_ref().type_id();
+        trace::spill(type_id);
+        self.buf.insert_dyn(r);
+        self.ttl.stamp(&self.buf, type_id);
+        #[cfg(feature = "metrics")]
+        self.stats.count_spill(type_id, self.buf.len());
+    }
+
+    /// Drops the oldest values until a value of type `type_id` fits, per 
+    /// [OverflowPolicy::DropOldest]. Returns false if it cannot fit.
+    fn make_room(&mut self, type_id: TypeId) -> bool
+    {
+        while self.limit.is_full(&self.buf, type_id)
+        {
+            let victim = match self.buf.contains(type_id)
+            {
+                true => Some(type_id),
+                false if self.limit.is_full_of(&self.buf, type_id) => None,
+                false => self.buf.find_type(&|_| true)
+            };
+            match victim.and_then(|victim| self.buf.remove_dyn(victim))
+            {
+                Some(old) => *self.dropped.entry(old.as_ref().type_id()).or_insert(0) += 1,
+                None => return false
+            }
+        }
+        true
+    }
+}
+
+impl From<AnyReceiver> for BufferedReceiver
+{
+    /// See [AnyReceiver::into_buffered].
+    #[inline]
+    fn from(rx: AnyReceiver) -> Self
+    {
+        rx.into_buffered()
+    }
+}
+
+impl From<mpsc::Receiver<Box<dyn Any>>> for BufferedReceiver
+{
+    /// Wraps `rx` with an empty buffer. `rx` should only ever be sent boxed
+    /// values by an [AnySender].
+    #[inline]
+    fn from(rx: mpsc::Receiver<Box<dyn Any>>) -> Self
+    {
+        BufferedReceiver::new(rx, TypeBuffer::new())
+    }
+}
+
+impl BufferedReceiver
+{
+    /// Converts this receiver back into a plain [AnyReceiver] on the same 
+    /// channel if the buffer is empty. Otherwise the receiver is handed back 
+    /// unchanged, so that the buffer can be drained first.
+    #[inline]
+    #[allow(clippy::result_large_err)]
+    pub fn into_unbuffered(self) -> Result<AnyReceiver, Self>
+    {
+        match self.buf.is_empty()
+        {
+            true => Ok(AnyReceiver(self.rx, Default::default(), self.casters, self.tap)),
+            false => Err(self)
+        }
+    }
+
+    /// Converts this receiver back into a plain [AnyReceiver] on the same 
+    /// channel. Values held in the buffer are returned alongside it so that 
+    /// nothing is lost; see [TypeBuffer::drain] for their order.
+    #[inline]
+    pub fn into_plain(mut self) -> (AnyReceiver, Vec<Box<dyn Any>>)
+    {
+        let values = self.fresh_buf().drain();
+        (AnyReceiver(self.rx, Default::default(), self.casters, self.tap), values)
+    }
+
+    /// Converts this receiver into a [SharedBufferedReceiver] that may be 
+    /// cloned and received from by several threads, all sharing one buffer.
+    #[inline]
+    pub fn into_shared(self) -> SharedBufferedReceiver
+    {
+        SharedBufferedReceiver(std::sync::Arc::new(std::sync::Mutex::new(self)))
+    }
+}
@@ -0,0 +1,119 @@
+use super::{mpsc, AnySender, AnyReceiver};
+use core::any::*;
+#[cfg(feature = "buf_recv")]
+use super::{BufferLimit, BufferedReceiver, OverflowPolicy, TypeBuffer};
+
+/// Configures a channel before creating it, as an alternative to the
+/// separate constructor functions. `ChannelBuilder::new().build()` is the
+/// same as [channel](crate::channel), and
+/// `ChannelBuilder::new().buffered().build()` the same as
+/// [buffered_channel](crate::buffered_channel).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChannelBuilder
+{
+    #[cfg(any(feature = "crossbeam", feature = "flume"))]
+    bound: Option<usize>
+}
+
+impl ChannelBuilder
+{
+    /// An unbounded channel without a buffer.
+    #[inline]
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// Makes sends block while `cap` messages are waiting. Only the
+    /// `crossbeam` and `flume` backends support bounded channels of the
+    /// [mpsc::Sender] type that [AnySender] wraps.
+    #[cfg(any(feature = "crossbeam", feature = "flume"))]
+    #[inline]
+    pub fn bounded(mut self, cap: usize) -> Self
+    {
+        self.bound = Some(cap);
+        self
+    }
+
+    /// Continues configuring a channel with a [BufferedReceiver].
+    #[cfg(feature = "buf_recv")]
+    #[inline]
+    pub fn buffered(self) -> BufferedChannelBuilder
+    {
+        BufferedChannelBuilder { channel: self, capacity: 0, limit: BufferLimit::default() }
+    }
+
+    /// Creates the channel.
+    #[inline]
+    pub fn build(self) -> (AnySender, AnyReceiver)
+    {
+        let (tx, rx) = self.raw();
+        (tx, AnyReceiver::new(rx))
+    }
+
+    #[inline]
+    fn raw(self) -> (AnySender, mpsc::Receiver<Box<dyn Any>>)
+    {
+        #[cfg(any(feature = "crossbeam", feature = "flume"))]
+        let (tx, rx) = match self.bound
+        {
+            Some(cap) => mpsc::bounded(cap),
+            None => mpsc::channel()
+        };
+        #[cfg(not(any(feature = "crossbeam", feature = "flume")))]
+        let (tx, rx) = mpsc::channel();
+        (AnySender::new(tx), rx)
+    }
+}
+
+/// Configures a channel with a [BufferedReceiver] before creating it.
+/// Created by [ChannelBuilder::buffered].
+#[cfg(feature = "buf_recv")]
+#[derive(Debug, Clone, Copy)]
+pub struct BufferedChannelBuilder
+{
+    channel: ChannelBuilder,
+    capacity: usize,
+    limit: BufferLimit
+}
+
+#[cfg(feature = "buf_recv")]
+impl BufferedChannelBuilder
+{
+    /// Gives the buffer room for values of `capacity` distinct types before
+    /// it reallocates. See [TypeBuffer::with_capacity].
+    #[inline]
+    pub fn with_buf_capacity(mut self, capacity: usize) -> Self
+    {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Limits the values held in the buffer. See
+    /// [BufferedReceiver::set_buffer_limit].
+    #[inline]
+    pub fn buffer_limit(mut self, limit: BufferLimit) -> Self
+    {
+        self.limit = limit;
+        self
+    }
+
+    /// Sets what happens to a value that would take the buffer past its
+    /// [BufferLimit], keeping the limit itself.
+    #[inline]
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> Self
+    {
+        self.limit.policy = policy;
+        self
+    }
+
+    /// Creates the channel.
+    #[inline]
+    pub fn build(self) -> (AnySender, BufferedReceiver)
+    {
+        let (tx, rx) = self.channel.raw();
+        let mut rx = BufferedReceiver::new(rx, TypeBuffer::with_capacity(self.capacity));
+        rx.set_buffer_limit(self.limit);
+        (tx, rx)
+    }
+}
@@ -0,0 +1,121 @@
+use super::{mpsc::{self, Sender}, AnySender, RawChannel, RawRecvError};
+use core::any::*;
+use std::{sync::{Arc, atomic::{AtomicBool, Ordering}}, time::{Duration, Instant}};
+
+/// Private message sent by [CancelToken::cancel] to wake a blocked receiver.
+/// It is never handed to the user: every receive method discards it.
+pub(crate) struct Wake;
+
+/// Allows a blocking receive on another thread to be cancelled.
+///
+/// A token is tied to one channel and holds its own clone of that channel's
+/// sender, which it uses to wake the receiver. Note that this means the
+/// channel will not report disconnection while a token for it is alive.
+#[derive(Debug, Clone)]
+pub struct CancelToken
+{
+    cancelled: Arc<AtomicBool>,
+    tx: Sender<Box<dyn Any>>
+}
+unsafe impl Send for CancelToken {}
+
+impl CancelToken
+{
+    /// Creates a token that can cancel receives on the channel `tx` sends to.
+    #[inline]
+    pub fn new(tx: &AnySender) -> Self
+    {
+        CancelToken { cancelled: Arc::new(AtomicBool::new(false)), tx: tx.0.clone() }
+    }
+
+    /// Cancels every current and future cancellable receive using this token
+    /// or any of its clones.
+    #[inline]
+    pub fn cancel(&self)
+    {
+        self.cancelled.store(true, Ordering::SeqCst);
+        // If the receiver is gone there is nothing left to wake
+        let _ = self.tx.send(Box::new(Wake));
+    }
+
+    /// Whether [CancelToken::cancel] has been called.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool
+    {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Receive operations that transparently discard [Wake] messages.
+pub(crate) trait ReceiverExt
+{
+    fn recv_msg(&self) -> Result<Box<dyn Any>, mpsc::RecvError>;
+    fn try_recv_msg(&self) -> Result<Box<dyn Any>, mpsc::TryRecvError>;
+    fn recv_timeout_msg(&self, timeout: Duration) -> Result<Box<dyn Any>, mpsc::RecvTimeoutError>;
+
+    /// Like `recv_msg`, but returns `None` once `token` is cancelled. The 
+    /// [Wake] sent by [CancelToken::cancel] ends the wait.
+    fn recv_msg_cancellable(&self, token: &CancelToken) -> Result<Option<Box<dyn Any>>, mpsc::RecvError>;
+}
+
+impl<C: RawChannel> ReceiverExt for C
+{
+    #[inline]
+    fn recv_msg(&self) -> Result<Box<dyn Any>, mpsc::RecvError>
+    {
+        loop
+        {
+            let r = self.recv().map_err(RawRecvError::into_recv_error)?;
+            if !r.is::<Wake>()
+            {
+                break Ok(r);
+            }
+        }
+    }
+
+    #[inline]
+    fn try_recv_msg(&self) -> Result<Box<dyn Any>, mpsc::TryRecvError>
+    {
+        loop
+        {
+            let r = self.try_recv().map_err(RawRecvError::into_try_recv_error)?;
+            if !r.is::<Wake>()
+            {
+                break Ok(r);
+            }
+        }
+    }
+
+    #[inline]
+    fn recv_timeout_msg(&self, timeout: Duration) -> Result<Box<dyn Any>, mpsc::RecvTimeoutError>
+    {
+        let start = Instant::now();
+        let mut remaining = timeout;
+        loop
+        {
+            let r = self.recv_timeout(remaining).map_err(RawRecvError::into_recv_timeout_error)?;
+            if !r.is::<Wake>()
+            {
+                break Ok(r);
+            }
+            remaining = timeout.saturating_sub(start.elapsed());
+        }
+    }
+
+    #[inline]
+    fn recv_msg_cancellable(&self, token: &CancelToken) -> Result<Option<Box<dyn Any>>, mpsc::RecvError>
+    {
+        loop
+        {
+            if token.is_cancelled()
+            {
+                break Ok(None);
+            }
+            let r = self.recv().map_err(RawRecvError::into_recv_error)?;
+            if !r.is::<Wake>()
+            {
+                break Ok(Some(r));
+            }
+        }
+    }
+}
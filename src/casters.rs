@@ -0,0 +1,119 @@
+use super::{trace, AnyReceiver, AnyRecvError, ReceiverExt};
+use core::any::*;
+use std::{collections::HashMap, fmt};
+
+type Caster<U> = Box<dyn Fn(Box<dyn Any>) -> Box<U> + Send>;
+
+/// Conversions from concrete types to trait objects, keyed by the concrete
+/// type and the target type. Each entry holds a `Caster` for its target.
+#[derive(Default)]
+pub(crate) struct Casters(HashMap<(TypeId, TypeId), Box<dyn Any + Send>>);
+
+impl Casters
+{
+    pub(crate) fn register<T: 'static, U: ?Sized + 'static>(&mut self, f: impl Fn(Box<T>) -> Box<U> + Send + 'static)
+    {
+        let caster: Caster<U> = Box::new(move |r| f(r.downcast().expect("caster called with a value of the wrong type")));
+        self.0.insert((TypeId::of::<T>(), TypeId::of::<U>()), Box::new(caster));
+    }
+
+    /// Whether values of type `type_id` can be cast to `U`.
+    #[cfg(feature = "buf_recv")]
+    #[inline]
+    pub(crate) fn has<U: ?Sized + 'static>(&self, type_id: TypeId) -> bool
+    {
+        self.0.contains_key(&(type_id, TypeId::of::<U>()))
+    }
+
+    /// Casts `r` to `U`, or hands it back if its type has no caster for `U`.
+    pub(crate) fn cast<U: ?Sized + 'static>(&self, r: Box<dyn Any>) -> Result<Box<U>, Box<dyn Any>>
+    {
+        match self.0.get(&(r.as_ref().type_id(), TypeId::of::<U>()))
+        {
+            Some(caster) => Ok(caster.downcast_ref::<Caster<U>>().expect("caster registered under the wrong target")(r)),
+            None => Err(r)
+        }
+    }
+}
+
+impl fmt::Debug for Casters
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_tuple("Casters")
+            .field(&self.0.len())
+            .finish()
+    }
+}
+
+/// Registers several casters to one trait object type at once. Each listed
+/// type is cast with an unsizing coercion, so it must implement the trait.
+///
+/// ```
+/// # use any_mpsc::register_casters;
+/// use std::fmt::Display;
+/// let (tx, mut rx) = any_mpsc::channel();
+/// register_casters!(rx, dyn Display => i32, String);
+/// tx.send(String::from("hello")).unwrap();
+/// tx.send(5i32).unwrap();
+/// assert_eq!(rx.recv_dyn::<dyn Display>().unwrap().to_string(), "hello");
+/// assert_eq!(rx.recv_dyn::<dyn Display>().unwrap().to_string(), "5");
+/// ```
+#[macro_export]
+macro_rules! register_casters
+{
+    ($rx:expr, $target:ty => $($ty:ty),+ $(,)?) =>
+    {
+        $($rx.register_caster::<$ty, $target>(|r| r as Box<$target>);)+
+    };
+}
+
+/// Registers a caster from `T` to a trait object type it implements, the 
+/// same as `register_trait::<T, dyn Trait>()` would if stable Rust could 
+/// express the unsizing coercion generically. See also 
+/// [register_casters](crate::register_casters).
+///
+/// ```
+/// # use any_mpsc::register_trait;
+/// use std::fmt::Display;
+/// let (tx, mut rx) = any_mpsc::channel();
+/// register_trait!(rx, u8 => dyn Display);
+/// tx.send(5u8).unwrap();
+/// assert_eq!(rx.recv_dyn::<dyn Display>().unwrap().to_string(), "5");
+/// ```
+#[macro_export]
+macro_rules! register_trait
+{
+    ($rx:expr, $ty:ty => $target:ty) =>
+    {
+        $rx.register_caster::<$ty, $target>(|r| r as Box<$target>)
+    };
+}
+
+impl AnyReceiver
+{
+    /// Allows values of type `T` to be received as `U`, usually a trait
+    /// object, by [AnyReceiver::recv_dyn]. `f` does the conversion, e.g.
+    /// `|r| r as Box<dyn Trait>`. See also
+    /// [register_casters](crate::register_casters).
+    #[inline]
+    pub fn register_caster<T: 'static, U: ?Sized + 'static>(&mut self, f: impl Fn(Box<T>) -> Box<U> + Send + 'static)
+    {
+        self.2.register(f);
+    }
+
+    /// Wraps [mpsc::Receiver::recv](std::sync::mpsc::Receiver::recv).
+    /// Receives a value of any type with a caster to `U` registered, and
+    /// returns it cast. A value of any other type is returned in a
+    /// [AnyRecvError::WrongType].
+    pub fn recv_dyn<U: ?Sized + 'static>(&self) -> Result<Box<U>, AnyRecvError>
+    {
+        let result = self
+            .recv_msg()
+            .map_err(AnyRecvError::RecvError)
+            .and_then(|r| self.2.cast(r).map_err(AnyRecvError::wrong_type));
+        trace::recv(&result);
+        result
+    }
+}
+
@@ -0,0 +1,151 @@
+use super::{mpsc, AnyReceiver, AnyRecvError};
+use core::any::*;
+use std::{cell::Cell, error::Error, fmt::{self, Display}, time::{Duration, Instant}};
+
+/// Several [AnyReceiver]s read one after another as a single stream. Each 
+/// receiver is read until it is disconnected and drained, then reading 
+/// continues transparently on the next. Created with [AnyReceiver::chain].
+#[derive(Debug)]
+pub struct ChainedAnyReceiver
+{
+    pub receivers: Vec<AnyReceiver>,
+    current: Cell<usize>
+}
+
+impl From<AnyReceiver> for ChainedAnyReceiver
+{
+    /// A chain of just `rx`, ready to be extended with 
+    /// [ChainedAnyReceiver::chain].
+    #[inline]
+    fn from(rx: AnyReceiver) -> Self
+    {
+        ChainedAnyReceiver { receivers: vec![rx], current: Cell::new(0) }
+    }
+}
+
+impl AnyReceiver
+{
+    /// Chains `other` after this receiver, so that it is read once this one
+    /// is disconnected and drained.
+    #[inline]
+    pub fn chain(self, other: AnyReceiver) -> ChainedAnyReceiver
+    {
+        ChainedAnyReceiver::from(self).chain(other)
+    }
+}
+
+impl ChainedAnyReceiver
+{
+    /// Appends `other`, which may itself be a chain, to the end of this 
+    /// chain.
+    #[inline]
+    pub fn chain(mut self, other: impl Into<ChainedAnyReceiver>) -> ChainedAnyReceiver
+    {
+        let other = other.into();
+        self.receivers.extend(other.receivers.into_iter().skip(other.current.get()));
+        self
+    }
+
+    /// Index in [ChainedAnyReceiver::receivers] of the receiver currently
+    /// being read.
+    #[inline]
+    pub fn current(&self) -> usize
+    {
+        self.current.get()
+    }
+
+    /// Wraps [AnyReceiver::recv] on the current receiver, moving on to the
+    /// next whenever it is disconnected.
+    #[inline]
+    pub fn recv<T: 'static>(&self) -> Result<T, ChainRecvError>
+    {
+        self.recv_with(AnyReceiver::recv)
+    }
+
+    /// Wraps [AnyReceiver::recv_timeout] on the current receiver, moving on 
+    /// to the next whenever it is disconnected. `timeout` applies to the 
+    /// whole receive, however many receivers it passes through.
+    #[inline]
+    pub fn recv_timeout<T: 'static>(&self, timeout: Duration) -> Result<T, ChainRecvError>
+    {
+        let deadline = Instant::now() + timeout;
+        self.recv_with(|rx| rx.recv_timeout(deadline.saturating_duration_since(Instant::now())))
+    }
+
+    /// Wraps [AnyReceiver::try_recv] on the current receiver, moving on to 
+    /// the next whenever it is disconnected.
+    #[inline]
+    pub fn try_recv<T: 'static>(&self) -> Result<T, ChainRecvError>
+    {
+        self.recv_with(AnyReceiver::try_recv)
+    }
+
+    /// Wraps [AnyReceiver::recv_any] on the current receiver, moving on to 
+    /// the next whenever it is disconnected.
+    #[inline]
+    pub fn recv_any(&self) -> Result<Box<dyn Any>, ChainRecvError>
+    {
+        self.recv_with(AnyReceiver::recv_any)
+    }
+
+    /// Wraps [AnyReceiver::try_recv_any] on the current receiver, moving on 
+    /// to the next whenever it is disconnected.
+    #[inline]
+    pub fn try_recv_any(&self) -> Result<Box<dyn Any>, ChainRecvError>
+    {
+        self.recv_with(AnyReceiver::try_recv_any)
+    }
+
+    fn recv_with<R>(&self, mut f: impl FnMut(&AnyReceiver) -> Result<R, AnyRecvError>) -> Result<R, ChainRecvError>
+    {
+        loop
+        {
+            let index = self.current.get();
+            match f(&self.receivers[index])
+            {
+                Err(error) if is_disconnected(&error) && index + 1 < self.receivers.len() => 
+                {
+                    self.current.set(index + 1);
+                }
+                result => break result.map_err(|error| ChainRecvError { index, error })
+            }
+        }
+    }
+}
+
+#[inline]
+fn is_disconnected(error: &AnyRecvError) -> bool
+{
+    matches!(error, 
+        AnyRecvError::RecvError(_)
+        | AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Disconnected)
+        | AnyRecvError::TryRecvError(mpsc::TryRecvError::Disconnected))
+}
+
+/// Error type for [ChainedAnyReceiver]. Wraps the error from the underlying 
+/// receiver along with its index in [ChainedAnyReceiver::receivers]. A 
+/// disconnect is only reported by the last receiver in the chain.
+#[derive(Debug)]
+pub struct ChainRecvError
+{
+    pub index: usize,
+    pub error: AnyRecvError
+}
+
+impl Display for ChainRecvError
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "Receiver {} of chain: {}", self.index, self.error)
+    }
+}
+
+impl Error for ChainRecvError
+{
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        Some(&self.error)
+    }
+}
@@ -0,0 +1,94 @@
+use super::AnyRecvError;
+use std::{error::Error, fmt};
+
+/// An [AnyRecvError] annotated with a message describing what was being 
+/// received. Displays as the message; the error is its 
+/// [source](Error::source).
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct AnyRecvContext
+{
+    pub message: String,
+    pub error: AnyRecvError
+}
+
+impl fmt::Display for AnyRecvContext
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for AnyRecvContext
+{
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        Some(&self.error)
+    }
+}
+
+impl AnyRecvError
+{
+    /// Annotates this error with `message`.
+    #[inline]
+    pub fn context(self, message: impl Into<String>) -> AnyRecvContext
+    {
+        AnyRecvContext { message: message.into(), error: self }
+    }
+}
+
+/// Annotates the error of a receive with a message, like 
+/// [AnyRecvError::context], so that it can be propagated with `?`.
+pub trait RecvContext<T>
+{
+    /// Annotates an error with `message`.
+    fn context(self, message: impl Into<String>) -> Result<T, AnyRecvContext>;
+
+    /// Annotates an error with the message returned by `f`, which is only 
+    /// called if there is an error.
+    fn with_context<M: Into<String>>(self, f: impl FnOnce() -> M) -> Result<T, AnyRecvContext>;
+}
+
+impl<T> RecvContext<T> for Result<T, AnyRecvError>
+{
+    #[inline]
+    fn context(self, message: impl Into<String>) -> Result<T, AnyRecvContext>
+    {
+        self.map_err(|err| err.context(message))
+    }
+
+    #[inline]
+    fn with_context<M: Into<String>>(self, f: impl FnOnce() -> M) -> Result<T, AnyRecvContext>
+    {
+        self.map_err(|err| err.context(f()))
+    }
+}
+
+/// A value returned in [AnyRecvError::WrongType] cannot be sent between 
+/// threads, so these errors are not `Send` and cannot use `anyhow`'s blanket
+/// `From` conversion. These go through [std::io::Error] instead, which keeps
+/// the error kind and message.
+#[cfg(feature = "anyhow")]
+impl AnyRecvError
+{
+    /// Converts this error into an [anyhow::Error].
+    #[inline]
+    pub fn into_anyhow(self) -> anyhow::Error
+    {
+        anyhow::Error::new(std::io::Error::from(self))
+    }
+}
+
+#[cfg(feature = "anyhow")]
+impl AnyRecvContext
+{
+    /// Converts this error into an [anyhow::Error], keeping the message as 
+    /// context on top of the converted [AnyRecvError].
+    #[inline]
+    pub fn into_anyhow(self) -> anyhow::Error
+    {
+        self.error.into_anyhow().context(self.message)
+    }
+}
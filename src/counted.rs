@@ -0,0 +1,168 @@
+use super::{mpsc, AnySender, AnyReceiver, AnyRecvError};
+use core::any::*;
+use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
+
+/// An [mpsc::channel] that supports dynamic typing and counts the messages
+/// passing through it. Both ends share the same counters.
+#[inline]
+pub fn counted_channel() -> (CountedSender, CountedReceiver)
+{
+    let (tx, rx) = super::channel();
+    let counters = Arc::new(Counters::default());
+    (CountedSender { tx, counters: counters.clone() }, CountedReceiver { rx, counters })
+}
+
+#[derive(Debug, Default)]
+struct Counters
+{
+    sent: AtomicU64,
+    received: AtomicU64,
+    wrong_type: AtomicU64
+}
+
+impl Counters
+{
+    #[inline]
+    fn record<T>(&self, result: &Result<T, AnyRecvError>)
+    {
+        let counter = match result
+        {
+            Ok(_) => &self.received,
+            Err(AnyRecvError::WrongType(..)) => &self.wrong_type,
+            Err(_) => return
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn in_flight(&self) -> u64
+    {
+        let taken = self.received.load(Ordering::Relaxed) + self.wrong_type.load(Ordering::Relaxed);
+        self.sent.load(Ordering::Relaxed).saturating_sub(taken)
+    }
+}
+
+/// Wraps an [AnySender] and counts successful sends.
+#[derive(Debug)]
+pub struct CountedSender
+{
+    pub tx: AnySender,
+    counters: Arc<Counters>
+}
+
+impl CountedSender
+{
+    /// Wraps [AnySender::send].
+    #[inline]
+    pub fn send<T: Any>(&self, t: T) -> Result<(), mpsc::SendError<Box<dyn Any>>>
+    {
+        self.tx.send(t)?;
+        self.counters.sent.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Number of messages successfully sent.
+    #[inline]
+    pub fn sent_count(&self) -> u64
+    {
+        self.counters.sent.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages received with the requested type.
+    #[inline]
+    pub fn recv_count(&self) -> u64
+    {
+        self.counters.received.load(Ordering::Relaxed)
+    }
+
+    /// Number of receives that failed with [AnyRecvError::WrongType].
+    #[inline]
+    pub fn wrong_type_count(&self) -> u64
+    {
+        self.counters.wrong_type.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages sent that have not yet been taken off the channel,
+    /// whether they were then received successfully or as the wrong type.
+    #[inline]
+    pub fn in_flight(&self) -> u64
+    {
+        self.counters.in_flight()
+    }
+}
+
+/// Wraps an [AnyReceiver] and counts successful and wrong-type receives.
+#[derive(Debug)]
+pub struct CountedReceiver
+{
+    pub rx: AnyReceiver,
+    counters: Arc<Counters>
+}
+
+impl CountedReceiver
+{
+    /// Wraps [AnyReceiver::recv].
+    #[inline]
+    pub fn recv<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        let result = self.rx.recv();
+        self.counters.record(&result);
+        result
+    }
+
+    /// Wraps [AnyReceiver::recv_timeout].
+    #[inline]
+    pub fn recv_timeout<T: 'static>(&self, timeout: std::time::Duration) -> Result<T, AnyRecvError>
+    {
+        let result = self.rx.recv_timeout(timeout);
+        self.counters.record(&result);
+        result
+    }
+
+    /// Wraps [AnyReceiver::try_recv].
+    #[inline]
+    pub fn try_recv<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        let result = self.rx.try_recv();
+        self.counters.record(&result);
+        result
+    }
+
+    /// Wraps [AnyReceiver::recv_any].
+    #[inline]
+    pub fn recv_any(&self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        let result = self.rx.recv_any();
+        self.counters.record(&result);
+        result
+    }
+
+    /// Number of messages successfully sent.
+    #[inline]
+    pub fn sent_count(&self) -> u64
+    {
+        self.counters.sent.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages received with the requested type.
+    #[inline]
+    pub fn recv_count(&self) -> u64
+    {
+        self.counters.received.load(Ordering::Relaxed)
+    }
+
+    /// Number of receives that failed with [AnyRecvError::WrongType].
+    #[inline]
+    pub fn wrong_type_count(&self) -> u64
+    {
+        self.counters.wrong_type.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages sent that have not yet been taken off the channel,
+    /// whether they were then received successfully or as the wrong type.
+    #[inline]
+    pub fn in_flight(&self) -> u64
+    {
+        self.counters.in_flight()
+    }
+}
@@ -0,0 +1,70 @@
+use super::AnyReceiver;
+use core::any::*;
+use std::{collections::HashMap, fmt};
+
+type Handler = Box<dyn FnMut(Box<dyn Any>)>;
+
+/// Routes dynamically typed values to handlers registered per type.
+#[derive(Default)]
+pub struct Dispatcher
+{
+    handlers: HashMap<TypeId, Handler>
+}
+
+impl fmt::Debug for Dispatcher
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("Dispatcher")
+            .field("handlers", &self.handlers.len())
+            .finish()
+    }
+}
+
+impl Dispatcher
+{
+    /// Creates a dispatcher with no handlers.
+    #[inline]
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// Registers `f` to be called with every dispatched value of type `T`,
+    /// replacing any handler previously registered for `T`.
+    pub fn register<T: 'static>(&mut self, mut f: impl FnMut(T) + 'static)
+    {
+        self.handlers.insert(TypeId::of::<T>(), Box::new(move |value: Box<dyn Any>|
+        {
+            f(*value.downcast().expect("Dispatcher handler called with a value of the wrong type"))
+        }));
+    }
+
+    /// Passes `value` to the handler registered for its type. If there is no
+    /// such handler, `value` is handed back.
+    #[inline]
+    pub fn dispatch(&mut self, value: Box<dyn Any>) -> Result<(), Box<dyn Any>>
+    {
+        match self.handlers.get_mut(&value.as_ref().type_id())
+        {
+            Some(handler) =>
+            {
+                handler(value);
+                Ok(())
+            },
+            None => Err(value)
+        }
+    }
+
+    /// Receives from `rx` and dispatches each value until every sender has
+    /// disconnected. Stops early and returns the value if one arrives that
+    /// has no handler.
+    pub fn run(&mut self, rx: &AnyReceiver) -> Result<(), Box<dyn Any>>
+    {
+        while let Ok(value) = rx.recv_any()
+        {
+            self.dispatch(value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use core::any::*;
+
+/// A value of one of two types, returned by `recv_either`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Either<A, B>
+{
+    Left(A),
+    Right(B)
+}
+
+/// Whether `type_id` is that of `A` or `B`.
+#[inline]
+pub(crate) fn is_either<A: 'static, B: 'static>(type_id: TypeId) -> bool
+{
+    type_id == TypeId::of::<A>() || type_id == TypeId::of::<B>()
+}
+
+/// Downcasts a value already known to pass [is_either]. `A` is tried first.
+#[inline]
+pub(crate) fn downcast_either<A: 'static, B: 'static>(r: Box<dyn Any>) -> Either<A, B>
+{
+    match r.downcast()
+    {
+        Ok(a) => Either::Left(*a),
+        Err(r) => Either::Right(*r.downcast().expect("value was checked to be an A or a B"))
+    }
+}
@@ -0,0 +1,67 @@
+use super::{mpsc, AnySender};
+use core::any::*;
+
+type SendResult = Result<(), mpsc::SendError<Box<dyn Any>>>;
+
+/// Combines several [AnySender]s into one [FanoutSender], which sends every 
+/// value to all of them.
+///
+/// This cannot return an [AnySender]: an `AnySender` is a single channel's
+/// sender, and the fanout has to clone each value for every channel, which
+/// needs `T: Clone` at the call to `send`, before the value is boxed. Its 
+/// `send` also reports the result of each channel rather than a single 
+/// [mpsc::SendError](std::sync::mpsc::SendError).
+#[inline]
+pub fn fanout_sender(senders: Vec<AnySender>) -> FanoutSender
+{
+    FanoutSender(senders)
+}
+
+/// Like [AnySender::send], but sends a clone of `value` on every sender in
+/// `senders`, and `value` itself on the last. Every sender is tried even if
+/// some fail, for instance because their receiver has been dropped. Returns
+/// the result of each send in the order of the senders.
+pub fn broadcast<T: Any + Clone>(senders: &[AnySender], value: T) -> Vec<SendResult>
+{
+    let mut results = Vec::with_capacity(senders.len());
+    if let Some((last, rest)) = senders.split_last()
+    {
+        results.extend(rest.iter().map(|tx| tx.send(value.clone())));
+        results.push(last.send(value));
+    }
+    results
+}
+
+/// Several [AnySender]s written to as one. A boxed value cannot be cloned, 
+/// so values are cloned as they are sent, which requires them to be 
+/// [Clone]. Created with [fanout_sender].
+///
+/// As with [AnySender], `FanoutSender::is_disconnected` is only available 
+/// with the `flume` backend. With the others, check the per-receiver 
+/// results of [FanoutSender::send].
+#[derive(Debug, Clone)]
+pub struct FanoutSender(pub Vec<AnySender>);
+
+impl FanoutSender
+{
+    /// Sends `t` on every sender, as [broadcast] does. If any fail, the 
+    /// result of each send is returned in the order of the senders.
+    pub fn send<T: Any + Clone>(&self, t: T) -> Result<(), Vec<SendResult>>
+    {
+        let results = broadcast(&self.0, t);
+        match results.iter().all(Result::is_ok)
+        {
+            true => Ok(()),
+            false => Err(results)
+        }
+    }
+
+    /// Whether every receiver has been dropped, so that every send would 
+    /// fail. See [AnySender::is_disconnected].
+    #[cfg(feature = "flume")]
+    #[inline]
+    pub fn is_disconnected(&self) -> bool
+    {
+        self.0.iter().all(AnySender::is_disconnected)
+    }
+}
@@ -0,0 +1,76 @@
+use super::{mpsc, typed_router::STOP_POLL, AnySender};
+use std::{sync::{Arc, atomic::{AtomicBool, AtomicU64, Ordering}}, thread::{self, JoinHandle}};
+
+/// Bridges a typed [mpsc::Receiver] into an [AnySender]. A forwarder thread 
+/// boxes each value received on `from` and sends it on `to`, until either 
+/// side disconnects or it is asked to stop through the returned 
+/// [ForwardHandle]. Several forwarders may send into clones of the same 
+/// [AnySender]; their values interleave in the order they are forwarded.
+pub fn forward<T: Send + 'static>(from: mpsc::Receiver<T>, to: AnySender) -> ForwardHandle
+{
+    let stop = Arc::new(AtomicBool::new(false));
+    let count = Arc::new(AtomicU64::new(0));
+    let (thread_stop, thread_count) = (stop.clone(), count.clone());
+    let thread = thread::spawn(move ||
+    {
+        while !thread_stop.load(Ordering::SeqCst)
+        {
+            let t = match from.recv_timeout(STOP_POLL)
+            {
+                Ok(t) => t,
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(_) => break
+            };
+            if to.send(t).is_err()
+            {
+                break;
+            }
+            thread_count.fetch_add(1, Ordering::SeqCst);
+        }
+        thread_count.load(Ordering::SeqCst)
+    });
+    ForwardHandle { stop, count, thread }
+}
+
+/// Controls the forwarder thread started by [forward]. Dropping the handle 
+/// leaves the forwarder running.
+#[derive(Debug)]
+pub struct ForwardHandle
+{
+    stop: Arc<AtomicBool>,
+    count: Arc<AtomicU64>,
+    thread: JoinHandle<u64>
+}
+
+impl ForwardHandle
+{
+    /// Asks the forwarder to stop. It finishes forwarding the value it is 
+    /// handling, if any, and stops within a few milliseconds.
+    #[inline]
+    pub fn stop(&self)
+    {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the forwarder has stopped.
+    #[inline]
+    pub fn is_finished(&self) -> bool
+    {
+        self.thread.is_finished()
+    }
+
+    /// Number of values forwarded so far.
+    #[inline]
+    pub fn count(&self) -> u64
+    {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Waits for the forwarder to stop and returns the total number of values
+    /// it forwarded. Returns an error if the forwarder panicked.
+    #[inline]
+    pub fn join(self) -> thread::Result<u64>
+    {
+        self.thread.join()
+    }
+}
@@ -0,0 +1,90 @@
+use super::{mpsc::Receiver, AnyBuffer, AnyReceiver, AnyRecvError, AnySender, BufferedReceiver, Dispatcher, OrderedBuffer};
+use core::any::*;
+
+/// An [mpsc::channel](std::sync::mpsc::channel) that supports dynamic typing
+/// and keeps send order across types. See [GlobalOrderedReceiver].
+#[inline]
+pub fn global_ordered_channel() -> (AnySender, GlobalOrderedReceiver)
+{
+    let (tx, rx) = super::channel();
+    (tx, rx.into_global_ordered())
+}
+
+/// Receives values of each requested type in strict send order. Values of 
+/// other types that arrive first are queued, in the order they arrived, 
+/// until they are asked for. Finding a queued value takes time linear in 
+/// the length of the queue.
+#[derive(Debug)]
+pub struct GlobalOrderedReceiver(pub BufferedReceiver<Receiver<Box<dyn Any>>, OrderedBuffer>);
+
+impl GlobalOrderedReceiver
+{
+    /// Wraps `rx`. Registered casters and any tap are kept.
+    #[inline]
+    pub fn new(rx: AnyReceiver) -> Self
+    {
+        GlobalOrderedReceiver(rx.into_buffered_with(OrderedBuffer::new()))
+    }
+
+    /// Returns the earliest queued `T`, or receives until a `T` arrives, 
+    /// queuing every other value.
+    #[inline]
+    pub fn recv<T: 'static>(&mut self) -> Result<T, AnyRecvError>
+    {
+        self.0.recv_until()
+    }
+
+    /// Like [GlobalOrderedReceiver::recv], but never blocks: values waiting 
+    /// on the channel are queued until a `T` is found or the channel is 
+    /// empty.
+    #[inline]
+    pub fn try_recv<T: 'static>(&mut self) -> Result<T, AnyRecvError>
+    {
+        self.0.try_recv_until()
+    }
+
+    /// Returns the earliest queued value of any type, or receives one if the
+    /// queue is empty.
+    #[inline]
+    pub fn recv_any(&mut self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        self.0.recv_any()
+    }
+
+    /// Passes every value to `dispatcher` in the order they were sent, 
+    /// queued values first, until every sender has disconnected. Stops early
+    /// and returns the value if one arrives that has no handler. See 
+    /// [Dispatcher::run].
+    pub fn dispatch(&mut self, dispatcher: &mut Dispatcher) -> Result<(), Box<dyn Any>>
+    {
+        while let Ok(r) = self.recv_any()
+        {
+            dispatcher.dispatch(r)?;
+        }
+        Ok(())
+    }
+
+    /// Number of queued values.
+    #[inline]
+    pub fn len(&self) -> usize
+    {
+        self.0.buffer_len()
+    }
+
+    /// Whether no values are queued.
+    #[inline]
+    pub fn is_empty(&self) -> bool
+    {
+        self.0.buf.is_empty()
+    }
+}
+
+impl AnyReceiver
+{
+    /// Converts this receiver into a [GlobalOrderedReceiver].
+    #[inline]
+    pub fn into_global_ordered(self) -> GlobalOrderedReceiver
+    {
+        GlobalOrderedReceiver::new(self)
+    }
+}
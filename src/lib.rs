@@ -2,53 +2,370 @@
 mod buffered_receiver;
 #[cfg(feature = "buf_recv")]
 pub use buffered_receiver::*;
+#[cfg(feature = "buf_recv")]
+mod type_buffer;
+#[cfg(feature = "buf_recv")]
+pub use type_buffer::*;
+#[cfg(feature = "buf_recv")]
+mod any_buffer;
+#[cfg(feature = "buf_recv")]
+pub use any_buffer::*;
+#[cfg(feature = "buf_recv")]
+mod ordered_buffer;
+#[cfg(feature = "buf_recv")]
+pub use ordered_buffer::*;
+#[cfg(feature = "buf_recv")]
+mod buffer_iter;
+#[cfg(feature = "buf_recv")]
+pub use buffer_iter::*;
+#[cfg(feature = "buf_recv")]
+mod buffer_ttl;
+#[cfg(feature = "buf_recv")]
+use buffer_ttl::BufferTtl;
+#[cfg(feature = "buf_recv")]
+mod buffer_handlers;
+#[cfg(feature = "buf_recv")]
+pub use buffer_handlers::Subscription;
+#[cfg(feature = "buf_recv")]
+use buffer_handlers::Handlers;
+#[cfg(feature = "buf_recv")]
+mod buffer_limit;
+#[cfg(feature = "buf_recv")]
+pub use buffer_limit::*;
+#[cfg(feature = "buf_recv")]
+mod global_ordered;
+#[cfg(feature = "buf_recv")]
+pub use global_ordered::*;
+mod builder;
+pub use builder::*;
+mod shared_receiver;
+pub use shared_receiver::*;
+mod spin_receiver;
+pub use spin_receiver::*;
+mod priority_receiver;
+pub use priority_receiver::*;
+mod cancel;
+pub use cancel::*;
+mod raw_channel;
+pub use raw_channel::*;
+mod type_names;
+pub use type_names::register_type_name;
+mod lookahead;
+use lookahead::Lookahead;
+mod trace;
+mod select;
+pub use select::*;
+mod dispatcher;
+pub use dispatcher::*;
+mod macros;
+mod chain;
+pub use chain::*;
+mod merge;
+pub use merge::*;
+mod fanout;
+pub use fanout::*;
+mod poison;
+pub use poison::*;
+mod typed_router;
+pub use typed_router::*;
+mod forward;
+pub use forward::*;
+mod timestamped;
+pub use timestamped::*;
+mod arc_channel;
+pub use arc_channel::*;
+mod context;
+pub use context::*;
+mod any_box;
+pub use any_box::*;
+mod either;
+pub use either::*;
+mod casters;
+use casters::Casters;
+mod tap;
+use tap::Tap;
+#[cfg(feature = "metrics")]
+mod counted;
+#[cfg(feature = "metrics")]
+pub use counted::*;
+#[cfg(all(feature = "metrics", feature = "buf_recv"))]
+mod recv_stats;
+#[cfg(all(feature = "metrics", feature = "buf_recv"))]
+pub use recv_stats::*;
+#[cfg(feature = "mock")]
+mod mock;
+#[cfg(feature = "mock")]
+pub use mock::*;
+#[cfg(feature = "test_utils")]
+mod recording;
+#[cfg(feature = "test_utils")]
+pub use recording::*;
+#[cfg(feature = "serde")]
+mod ser_channel;
+#[cfg(feature = "serde")]
+pub use ser_channel::*;
+#[cfg(all(feature = "serde", feature = "buf_recv"))]
+mod snapshot;
+#[cfg(all(feature = "serde", feature = "buf_recv"))]
+pub use snapshot::*;
+#[cfg(feature = "async")]
+mod async_flume;
+#[cfg(feature = "async")]
+pub use async_flume::*;
 
 use core::any::*;
-use std::{error::Error, fmt::Display, sync::mpsc::{self, Sender, Receiver}};
+use std::{error::Error, fmt::Display, ops::ControlFlow};
+use mpsc::{Sender, Receiver};
+
+/// The channel implementation backing this crate. Defaults to 
+/// [std::sync::mpsc]; the `crossbeam` and `flume` features swap in 
+/// `crossbeam_channel` or `flume`, which provide the same types under the 
+/// same names. If both features are enabled, `flume` is used.
+#[cfg(not(any(feature = "crossbeam", feature = "flume")))]
+pub(crate) mod mpsc
+{
+    pub use std::sync::mpsc::*;
+
+    /// The [RecvError] reported once every sender is gone.
+    pub const DISCONNECTED: RecvError = RecvError;
+}
+#[cfg(all(feature = "crossbeam", not(feature = "flume")))]
+pub(crate) mod mpsc
+{
+    pub use crossbeam_channel::*;
+
+    /// The [RecvError] reported once every sender is gone.
+    pub const DISCONNECTED: RecvError = RecvError;
+
+    /// Equivalent of [std::sync::mpsc::channel].
+    #[inline]
+    pub fn channel<T>() -> (Sender<T>, Receiver<T>)
+    {
+        unbounded()
+    }
+}
+#[cfg(feature = "flume")]
+pub(crate) mod mpsc
+{
+    pub use flume::*;
+
+    /// The [RecvError] reported once every sender is gone.
+    pub const DISCONNECTED: RecvError = RecvError::Disconnected;
+
+    /// Equivalent of [std::sync::mpsc::channel].
+    #[inline]
+    pub fn channel<T>() -> (Sender<T>, Receiver<T>)
+    {
+        unbounded()
+    }
+}
 
 /// An [mpsc::channel] that supports dynamic typing.
 #[inline]
 pub fn channel() -> (AnySender, AnyReceiver)
 {
     let (tx, rx) = mpsc::channel();
-    (AnySender(tx), AnyReceiver(rx))
+    (AnySender::new(tx), AnyReceiver::new(rx))
 }
 
-/// Wraps an [mpsc::Sender] to support dynamic typing.
-#[derive(Debug)]
+/// An [mpsc::channel] that supports dynamic typing, backed by 
+/// `flume::unbounded`. Identical to [channel] when the `flume` feature is 
+/// enabled.
+#[cfg(feature = "flume")]
+#[inline]
+pub fn flume_channel() -> (AnySender, AnyReceiver)
+{
+    channel()
+}
+
+/// An [mpsc::channel] that supports dynamic typing, backed by 
+/// `flume::bounded`. Sends block while `cap` messages are waiting.
+#[cfg(feature = "flume")]
+#[inline]
+pub fn flume_bounded_channel(cap: usize) -> (AnySender, AnyReceiver)
+{
+    let (tx, rx) = flume::bounded(cap);
+    (AnySender::new(tx), AnyReceiver::new(rx))
+}
+
+/// Wraps an [mpsc::Sender] to support dynamic typing. Values must be 
+/// `'static`, since a [TypeId] only exists for `'static` types and 
+/// downcasting a borrowed value could hand it back with the wrong lifetime.
+/// Senders and receivers may still be used inside [std::thread::scope], 
+/// with borrowed data passed by other means.
+///
+/// Only the `flume` backend can tell whether the receiver has been dropped
+/// without sending anything, so `AnySender::is_disconnected` is only 
+/// available with it. The std and crossbeam senders offer no such query, 
+/// and `AnySender` holds nothing but the backend's sender, so with those 
+/// backends a failed [AnySender::send] is the only sign of disconnection.
+#[derive(Debug, Clone)]
 pub struct AnySender(pub Sender<Box<dyn Any>>);
 unsafe impl Send for AnySender {}
 
 impl AnySender
 {
-    /// Wraps [mpsc::Sender::send].
+    /// Wraps `tx`. The same as `AnySender(tx)`.
+    #[inline]
+    pub fn new(tx: Sender<Box<dyn Any>>) -> Self
+    {
+        AnySender(tx)
+    }
+
+    /// Wraps the backend's `Sender::same_channel`: whether `self` and `other`
+    /// send to the same channel. Only the `crossbeam` and `flume` backends 
+    /// provide it; [std::sync::mpsc::Sender] cannot be compared. There is no
+    /// `AnyReceiver::same_sender` to find the sender paired with a receiver,
+    /// since no backend can compare a receiver with a sender.
+    #[cfg(any(feature = "crossbeam", feature = "flume"))]
+    #[inline]
+    pub fn same_channel(&self, other: &AnySender) -> bool
+    {
+        self.0.same_channel(&other.0)
+    }
+
+    /// Wraps [mpsc::Sender::send]. With the `type_names` feature, the name
+    /// of `T` is recorded so that receivers can report it (see 
+    /// [AnyRecvError::received_type_name]).
     #[inline]
     pub fn send<T: Any>(&self, t: T) -> Result<(), mpsc::SendError<Box<dyn Any>>>
     {
+        #[cfg(feature = "type_names")]
+        type_names::record::<T>();
+        trace::send::<T>();
         self.0.send(Box::new(t))
     }
+
+    /// Wraps [mpsc::Sender::send] for a value that is already boxed, such as
+    /// one from [AnyReceiver::recv_any]. Its type name is never recorded; 
+    /// see [register_type_name].
+    #[inline]
+    pub fn send_boxed(&self, t: Box<dyn Any>) -> Result<(), mpsc::SendError<Box<dyn Any>>>
+    {
+        self.0.send(t)
+    }
+
+    /// Wraps `flume::Sender::is_disconnected`: whether the receiver has been
+    /// dropped, so that every send would fail. Only the `flume` backend can 
+    /// tell without sending something; with the others, a failed 
+    /// [AnySender::send] is the signal.
+    #[cfg(feature = "flume")]
+    #[inline]
+    pub fn is_disconnected(&self) -> bool
+    {
+        self.0.is_disconnected()
+    }
 }
 
-/// Wraps an [mpsc::Receiver] to support dynamic typing.
+/// Wraps an [mpsc::Receiver] to support dynamic typing. A value may be held
+/// back from the channel after a probe such as [AnyReceiver::is_disconnected];
+/// it is always the next value received. The inner [mpsc::Receiver] is 
+/// private so that it cannot be received from directly, skipping that 
+/// value. Create one with [AnyReceiver::new] or [From].
 #[derive(Debug)]
-pub struct AnyReceiver(pub Receiver<Box<dyn Any>>);
+pub struct AnyReceiver(Receiver<Box<dyn Any>>, Lookahead, Casters, Tap);
 unsafe impl Send for AnyReceiver {}
 
+impl From<Receiver<Box<dyn Any>>> for AnyReceiver
+{
+    /// See [AnyReceiver::new].
+    #[inline]
+    fn from(rx: Receiver<Box<dyn Any>>) -> Self
+    {
+        AnyReceiver::new(rx)
+    }
+}
+
 impl AnyReceiver
 {
+    /// Wraps `rx`. It should only ever be sent boxed values by an 
+    /// [AnySender].
+    #[inline]
+    pub fn new(rx: Receiver<Box<dyn Any>>) -> Self
+    {
+        AnyReceiver(rx, Lookahead::default(), Casters::default(), Tap::default())
+    }
+
+    /// If a value is waiting, returns its [TypeId] without blocking. The 
+    /// value is held back and returned by the next receive, so probing never
+    /// loses or reorders anything. This serves as a peek; `ready().is_some()`
+    /// tells whether any value is waiting.
+    pub fn ready(&self) -> Option<TypeId>
+    {
+        if let Some(type_id) = self.1.type_id()
+        {
+            return Some(type_id);
+        }
+        let r = self.0.try_recv_msg().ok()?;
+        let type_id = r.as_ref().type_id();
+        self.1.put(r);
+        Some(type_id)
+    }
+
+    /// Whether a value of type `T` is waiting. See [AnyReceiver::ready].
+    #[inline]
+    pub fn ready_is<T: 'static>(&self) -> bool
+    {
+        self.ready() == Some(TypeId::of::<T>())
+    }
+
+    /// Whether every sender has been dropped and nothing is left to receive,
+    /// so that any further receive would fail. Never blocks or loses a 
+    /// value: one taken off the channel to check is held back and returned by
+    /// the next receive.
+    pub fn is_disconnected(&self) -> bool
+    {
+        if self.1.is_some()
+        {
+            return false;
+        }
+        match self.0.try_recv_msg()
+        {
+            Ok(r) =>
+            {
+                self.1.put(r);
+                false
+            },
+            Err(err) => err == mpsc::TryRecvError::Disconnected
+        }
+    }
+
     /// Wraps [mpsc::Receiver::recv]. See [crate::AnyRecvError] for details on the 
     /// return value.
     #[inline]
     pub fn recv<T: 'static>(&self) -> Result<T, AnyRecvError>
     {
-        self.0
-            .recv()
+        let result = self
+            .recv_msg()
             .map_err(AnyRecvError::RecvError)
             .and_then(|r| match r.downcast()
             {
                 Ok(r) => Ok(*r),
-                Err(r) => Err(AnyRecvError::WrongType(r)),
-            })
+                Err(r) => Err(AnyRecvError::wrong_type(r)),
+            });
+        trace::recv(&result);
+        result
+    }
+
+    /// Like [AnyReceiver::recv], but also returns the [TypeId] of `T`.
+    #[inline]
+    pub fn recv_with_typeid<T: 'static>(&self) -> Result<(T, TypeId), AnyRecvError>
+    {
+        self.recv().map(|t| (t, TypeId::of::<T>()))
+    }
+
+    /// Like [AnyReceiver::recv], but returns [ControlFlow::Break] once every
+    /// sender has disconnected, so that a consumer loop can stop on it 
+    /// without matching on the error. Any other outcome is returned in 
+    /// [ControlFlow::Continue].
+    #[inline]
+    pub fn recv_or_shutdown<T: 'static>(&self) -> ControlFlow<(), Result<T, AnyRecvError>>
+    {
+        match self.recv()
+        {
+            Err(AnyRecvError::RecvError(_)) => ControlFlow::Break(()),
+            result => ControlFlow::Continue(result)
+        }
     }
 
     /// Wraps [mpsc::Receiver::recv_timeout]. See [crate::AnyRecvError] for 
@@ -56,14 +373,24 @@ impl AnyReceiver
     #[inline]
     pub fn recv_timeout<T: 'static>(&self, timeout: std::time::Duration) -> Result<T, AnyRecvError>
     {
-        self.0
-            .recv_timeout(timeout)
+        let result = self
+            .recv_timeout_msg(timeout)
             .map_err(AnyRecvError::RecvTimeoutError)
             .and_then(|r| match r.downcast()
             {
                 Ok(r) => Ok(*r),
-                Err(r) => Err(AnyRecvError::WrongType(r)),
-            })
+                Err(r) => Err(AnyRecvError::wrong_type(r)),
+            });
+        trace::recv(&result);
+        result
+    }
+
+    /// Like [AnyReceiver::recv_timeout], but waits until `deadline` rather 
+    /// than for a duration.
+    #[inline]
+    pub fn recv_deadline<T: 'static>(&self, deadline: std::time::Instant) -> Result<T, AnyRecvError>
+    {
+        self.recv_timeout(deadline.saturating_duration_since(std::time::Instant::now()))
     }
 
     /// Wraps [mpsc::Receiver::try_recv]. See [crate::AnyRecvError] for 
@@ -71,35 +398,336 @@ impl AnyReceiver
     #[inline]
     pub fn try_recv<T: 'static>(&self) -> Result<T, AnyRecvError>
     {
-        self.0
-            .try_recv()
+        let result = self
+            .try_recv_msg()
             .map_err(AnyRecvError::TryRecvError)
             .and_then(|r| match r.downcast()
             {
                 Ok(r) => Ok(*r),
-                Err(r) => Err(AnyRecvError::WrongType(r)),
+                Err(r) => Err(AnyRecvError::wrong_type(r)),
+            });
+        trace::recv(&result);
+        result
+    }
+
+    /// Wraps [AnyReceiver::recv], returning `default` if the channel is 
+    /// disconnected. A value of the wrong type is discarded and `default` 
+    /// returned.
+    #[inline]
+    pub fn recv_or<T: 'static>(&self, default: T) -> T
+    {
+        self.recv().unwrap_or(default)
+    }
+
+    /// Wraps [AnyReceiver::recv_timeout], returning `default` if no value 
+    /// arrives within `timeout` or the channel is disconnected. A value of 
+    /// the wrong type is discarded and `default` returned.
+    #[inline]
+    pub fn recv_timeout_or<T: 'static>(&self, timeout: std::time::Duration, default: T) -> T
+    {
+        self.recv_timeout(timeout).unwrap_or(default)
+    }
+
+    /// Wraps [AnyReceiver::recv_timeout], returning `T::default()` if no 
+    /// value arrives within `timeout` or the channel is disconnected. A value
+    /// of the wrong type is discarded and `T::default()` returned.
+    #[inline]
+    pub fn recv_timeout_or_default<T: 'static + Default>(&self, timeout: std::time::Duration) -> T
+    {
+        self.recv_timeout(timeout).unwrap_or_default()
+    }
+
+    /// Like [AnyReceiver::recv_timeout_or_default], but waits until 
+    /// `deadline` rather than for a duration.
+    #[inline]
+    pub fn recv_deadline_or_default<T: 'static + Default>(&self, deadline: std::time::Instant) -> T
+    {
+        self.recv_timeout_or_default(deadline.saturating_duration_since(std::time::Instant::now()))
+    }
+
+    /// Converts this receiver into a [SharedReceiver] that may be cloned and
+    /// received from by several threads.
+    #[inline]
+    pub fn into_shared(self) -> SharedReceiver
+    {
+        SharedReceiver(std::sync::Arc::new(std::sync::Mutex::new(self)))
+    }
+
+    /// Converts this receiver into a [BufferedReceiver] on the same channel.
+    /// The buffer starts empty, apart from any value held back by a probe 
+    /// such as [AnyReceiver::is_disconnected]. Registered casters and any tap
+    /// are kept.
+    #[cfg(feature = "buf_recv")]
+    #[inline]
+    pub fn into_buffered(self) -> BufferedReceiver
+    {
+        self.into_buffered_with(TypeBuffer::new())
+    }
+
+    /// Like [AnyReceiver::into_buffered], but starts from the values in 
+    /// `buf`, for instance those handed over by another receiver. Values 
+    /// waiting on the channel are received after them.
+    #[cfg(feature = "buf_recv")]
+    pub fn into_buffered_with<B: AnyBuffer>(self, mut buf: B) -> BufferedReceiver<Receiver<Box<dyn Any>>, B>
+    {
+        if let Some(r) = self.1.take()
+        {
+            buf.insert_dyn(r);
+        }
+        let mut rx = BufferedReceiver::new(self.0, buf);
+        rx.casters = self.2;
+        rx.tap = self.3;
+        rx
+    }
+
+    /// Wraps [AnyReceiver::recv], applying `f` to the received value. A value
+    /// of the wrong type is returned in [AnyRecvError::WrongType] untouched.
+    #[inline]
+    pub fn recv_map<T: 'static, U>(&self, f: impl FnOnce(T) -> U) -> Result<U, AnyRecvError>
+    {
+        self.recv::<T>().map(f)
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Returns [AnyRecvError::Cancelled] if 
+    /// `token` is cancelled before a value arrives, without consuming 
+    /// anything from the channel.
+    pub fn recv_cancellable<T: 'static>(&self, token: &CancelToken) -> Result<T, AnyRecvError>
+    {
+        let result = match self.recv_msg_cancellable(token)
+        {
+            Ok(Some(r)) => r.downcast().map(|r| *r).map_err(AnyRecvError::wrong_type),
+            Ok(None) => Err(AnyRecvError::Cancelled),
+            Err(err) => Err(AnyRecvError::RecvError(err))
+        };
+        trace::recv(&result);
+        result
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Returns the received value without
+    /// attempting to downcast it.
+    #[inline]
+    pub fn recv_any(&self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        let result = self
+            .recv_msg()
+            .map_err(AnyRecvError::RecvError);
+        trace::recv_any(&result);
+        result
+    }
+
+    /// Like [AnyReceiver::recv_any], but also returns the [TypeId] of the 
+    /// value in the box.
+    #[inline]
+    pub fn recv_any_with_typeid(&self) -> Result<(Box<dyn Any>, TypeId), AnyRecvError>
+    {
+        self.recv_any().map(|r| 
+        {
+            let type_id = r.as_ref().type_id();
+            (r, type_id)
+        })
+    }
+
+    /// Wraps [mpsc::Receiver::recv_timeout]. Returns the received value 
+    /// without attempting to downcast it, along with its [TypeId].
+    #[inline]
+    pub fn recv_boxed_timeout(&self, timeout: std::time::Duration) -> Result<(TypeId, Box<dyn Any>), AnyRecvError>
+    {
+        let result = self
+            .recv_timeout_msg(timeout)
+            .map_err(AnyRecvError::RecvTimeoutError);
+        trace::recv_any(&result);
+        result.map(|r| (r.as_ref().type_id(), r))
+    }
+
+    /// Wraps [mpsc::Receiver::try_recv]. Returns the received value without
+    /// attempting to downcast it.
+    #[inline]
+    pub fn try_recv_any(&self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        let result = self
+            .try_recv_msg()
+            .map_err(AnyRecvError::TryRecvError);
+        trace::recv_any(&result);
+        result
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Receives a value of any type that passes
+    /// `pred`. A value whose type does not pass is returned in a
+    /// [AnyRecvError::WrongType].
+    #[inline]
+    pub fn recv_filter(&self, pred: impl Fn(TypeId) -> bool) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        self
+            .recv_msg()
+            .map_err(AnyRecvError::RecvError)
+            .and_then(|r| match pred(r.as_ref().type_id())
+            {
+                true => Ok(r),
+                false => Err(AnyRecvError::wrong_type(r)),
+            })
+    }
+
+    /// Wraps [mpsc::Receiver::try_recv]. Receives a value of any type that 
+    /// passes `pred`. A value whose type does not pass is returned in a
+    /// [AnyRecvError::WrongType].
+    #[inline]
+    pub fn try_recv_filter(&self, pred: impl Fn(TypeId) -> bool) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        self
+            .try_recv_msg()
+            .map_err(AnyRecvError::TryRecvError)
+            .and_then(|r| match pred(r.as_ref().type_id())
+            {
+                true => Ok(r),
+                false => Err(AnyRecvError::wrong_type(r)),
             })
     }
+
+    /// Wraps [mpsc::Receiver::recv]. Receives a value of type `A` or `B`. A
+    /// value of any other type is returned in a [AnyRecvError::WrongType].
+    #[inline]
+    pub fn recv_either<A: 'static, B: 'static>(&self) -> Result<Either<A, B>, AnyRecvError>
+    {
+        self.recv_filter(either::is_either::<A, B>)
+            .map(either::downcast_either)
+    }
+
+    /// Wraps [AnyReceiver::recv]. Will continuously recv until a value of type
+    /// `T` that passes `pred` is found. Values of type `T` that do not pass 
+    /// are discarded; a value of any other type is returned in a 
+    /// [AnyRecvError::WrongType].
+    pub fn filter_recv<T: 'static>(&self, pred: impl Fn(&T) -> bool) -> Result<T, AnyRecvError>
+    {
+        loop
+        {
+            let t = self.recv::<T>()?;
+            if pred(&t)
+            {
+                break Ok(t);
+            }
+        }
+    }
+
+    /// Consumes the receiver and returns every value still waiting on the
+    /// channel, in the order they were sent. Never blocks: stops as soon as
+    /// the channel is empty or disconnected.
+    pub fn drain(self) -> Vec<Box<dyn Any>>
+    {
+        let mut values = Vec::new();
+        while let Ok(r) = self.try_recv_msg()
+        {
+            values.push(r);
+        }
+        values
+    }
+
+    /// Like [AnyReceiver::drain], but separates the values of type `T` from
+    /// the rest.
+    #[inline]
+    pub fn drain_of<T: 'static>(self) -> (Vec<T>, Vec<Box<dyn Any>>)
+    {
+        partition(self.drain())
+    }
+}
+
+/// Splits `values` into those of type `T` and the rest, preserving order.
+pub(crate) fn partition<T: 'static>(values: Vec<Box<dyn Any>>) -> (Vec<T>, Vec<Box<dyn Any>>)
+{
+    let mut matched = Vec::new();
+    let mut rest = Vec::new();
+    for r in values
+    {
+        match r.downcast()
+        {
+            Ok(r) => matched.push(*r),
+            Err(r) => rest.push(r)
+        }
+    }
+    (matched, rest)
 }
 
 /// Error type for receievers. If an [mpsc] error occurs, it will be wrapped
 /// by an appropriate wrapper variant. If receiver is supplied an incorrect type, 
-/// a [AnyRecvError::WrongType(Box<dyn Any>)] will be returned containing the result 
-/// that did not successfully downcast. If buffered receiver is supplied an 
+/// a [AnyRecvError::WrongType(TypeId, Box<dyn Any>)] will be returned containing 
+/// the result that did not successfully downcast, along with its [TypeId]. If buffered receiver is supplied an 
 /// incorrect type, a [BufRecvError::WrongType(TypeId)] will be returned and the 
 /// result will be stored in a buffer. If [BufferedReceiver::recv_buf] is called
-/// with an empty buffer, EmptyBuffer will be returned
+/// with an empty buffer, EmptyBuffer will be returned. If a value is dropped
+/// because the buffer is full under [OverflowPolicy::Error], BufferFull will
+/// be returned. If [BufferedReceiver::recv_until_bounded] buffers more values
+/// than allowed, BufferLimitReached will be returned with the number it
+/// buffered. If a receive is cancelled through a [CancelToken], Cancelled
+/// will be returned. If a [PoisonableAnyReceiver]'s channel was poisoned by 
+/// a panic, Poisoned will be returned.
 #[derive(Debug)]
 pub enum AnyRecvError
 {
     RecvError(mpsc::RecvError),
     RecvTimeoutError(mpsc::RecvTimeoutError),
     TryRecvError(mpsc::TryRecvError),
-    WrongType(Box<dyn Any>),
+    WrongType(TypeId, Box<dyn Any>),
+    Cancelled,
+    Poisoned,
     #[cfg(feature = "buf_recv")]
     BufRecvError(TypeId),
     #[cfg(feature = "buf_recv")]
-    EmptyBuffer
+    EmptyBuffer,
+    #[cfg(feature = "buf_recv")]
+    BufferFull(TypeId),
+    #[cfg(feature = "buf_recv")]
+    BufferLimitReached(usize)
+}
+
+impl AnyRecvError
+{
+    /// Creates a [AnyRecvError::WrongType] for `r`, capturing its [TypeId].
+    #[inline]
+    pub fn wrong_type(r: Box<dyn Any>) -> Self
+    {
+        AnyRecvError::WrongType(r.as_ref().type_id(), r)
+    }
+
+    /// If this error was caused by receiving a value of the wrong type, 
+    /// returns the name of the type that actually arrived. This is `None`
+    /// unless the type was registered with [register_type_name], or sent by 
+    /// [AnySender::send] with the `type_names` feature enabled.
+    pub fn received_type_name(&self) -> Option<&'static str>
+    {
+        match self
+        {
+            AnyRecvError::WrongType(type_id, _) => type_names::lookup(*type_id),
+            #[cfg(feature = "buf_recv")]
+            AnyRecvError::BufRecvError(type_id) => type_names::lookup(*type_id),
+            _ => None
+        }
+    }
+
+    /// If this is a [AnyRecvError::WrongType] holding a value of type `T`, 
+    /// borrows that value without consuming the error.
+    #[inline]
+    pub fn recover_as<T: 'static>(&self) -> Option<&T>
+    {
+        match self
+        {
+            AnyRecvError::WrongType(_, r) => r.downcast_ref(),
+            _ => None
+        }
+    }
+
+    /// If this is a [AnyRecvError::WrongType] holding a value of type `T`, 
+    /// returns that value. Otherwise the error is handed back unchanged.
+    pub fn into_recovered<T: 'static>(self) -> Result<T, AnyRecvError>
+    {
+        match self
+        {
+            AnyRecvError::WrongType(type_id, r) => match r.downcast()
+            {
+                Ok(t) => Ok(*t),
+                Err(r) => Err(AnyRecvError::WrongType(type_id, r))
+            },
+            err => Err(err)
+        }
+    }
 }
 
 impl Display for AnyRecvError
@@ -112,9 +740,21 @@ impl Display for AnyRecvError
             AnyRecvError::RecvError(err) => err.fmt(f), 
             AnyRecvError::RecvTimeoutError(err) => err.fmt(f),
             AnyRecvError::TryRecvError(err) => err.fmt(f),
-            AnyRecvError::WrongType(_) => write!(f, "Received wrong type"),
-            AnyRecvError::BufRecvError(type_id) => write!(f, "Received wrong type: {:?}", type_id),
+            AnyRecvError::WrongType(..) => match self.received_type_name()
+            {
+                Some(name) => write!(f, "Received wrong type: {}", name),
+                None => write!(f, "Received wrong type"),
+            },
+            AnyRecvError::Cancelled => write!(f, "Receive was cancelled"),
+            AnyRecvError::Poisoned => write!(f, "Channel was poisoned by a panicking sender"),
+            #[cfg(feature = "buf_recv")]
+            AnyRecvError::BufRecvError(type_id) => write!(f, "Received wrong type: {}", type_names::TypeName(*type_id)),
+            #[cfg(feature = "buf_recv")]
             AnyRecvError::EmptyBuffer => write!(f, "Buffer is empty"),
+            #[cfg(feature = "buf_recv")]
+            AnyRecvError::BufferFull(type_id) => write!(f, "Buffer is full, dropped value of type: {}", type_names::TypeName(*type_id)),
+            #[cfg(feature = "buf_recv")]
+            AnyRecvError::BufferLimitReached(buffered) => write!(f, "Buffered {} values without finding the requested type", buffered),
         }
     }
 }
@@ -134,6 +774,93 @@ impl Error for AnyRecvError
     }
 }
 
+/// [AnyRecvError::WrongType] values compare equal if their [TypeId]s match;
+/// the values themselves are not compared.
+impl PartialEq for AnyRecvError
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        match (self, other)
+        {
+            (AnyRecvError::RecvError(a), AnyRecvError::RecvError(b)) => a == b,
+            (AnyRecvError::RecvTimeoutError(a), AnyRecvError::RecvTimeoutError(b)) => a == b,
+            (AnyRecvError::TryRecvError(a), AnyRecvError::TryRecvError(b)) => a == b,
+            (AnyRecvError::WrongType(a, _), AnyRecvError::WrongType(b, _)) => a == b,
+            (AnyRecvError::Cancelled, AnyRecvError::Cancelled) => true,
+            (AnyRecvError::Poisoned, AnyRecvError::Poisoned) => true,
+            #[cfg(feature = "buf_recv")]
+            (AnyRecvError::BufRecvError(a), AnyRecvError::BufRecvError(b)) => a == b,
+            #[cfg(feature = "buf_recv")]
+            (AnyRecvError::EmptyBuffer, AnyRecvError::EmptyBuffer) => true,
+            #[cfg(feature = "buf_recv")]
+            (AnyRecvError::BufferFull(a), AnyRecvError::BufferFull(b)) => a == b,
+            #[cfg(feature = "buf_recv")]
+            (AnyRecvError::BufferLimitReached(a), AnyRecvError::BufferLimitReached(b)) => a == b,
+            _ => false
+        }
+    }
+}
+
+impl Eq for AnyRecvError {}
+
+impl std::hash::Hash for AnyRecvError
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H)
+    {
+        core::mem::discriminant(self).hash(state);
+        match self
+        {
+            AnyRecvError::RecvTimeoutError(err) => (*err == mpsc::RecvTimeoutError::Timeout).hash(state),
+            AnyRecvError::TryRecvError(err) => (*err == mpsc::TryRecvError::Empty).hash(state),
+            AnyRecvError::WrongType(type_id, _) => type_id.hash(state),
+            #[cfg(feature = "buf_recv")]
+            AnyRecvError::BufRecvError(type_id) => type_id.hash(state),
+            #[cfg(feature = "buf_recv")]
+            AnyRecvError::BufferFull(type_id) => type_id.hash(state),
+            #[cfg(feature = "buf_recv")]
+            AnyRecvError::BufferLimitReached(buffered) => buffered.hash(state),
+            _ => ()
+        }
+    }
+}
+
+impl From<AnyRecvError> for std::io::Error
+{
+    /// Maps disconnection to [BrokenPipe](std::io::ErrorKind::BrokenPipe), an
+    /// empty channel or buffer to [WouldBlock](std::io::ErrorKind::WouldBlock),
+    /// a timeout to [TimedOut](std::io::ErrorKind::TimedOut), a wrong type to
+    /// [InvalidData](std::io::ErrorKind::InvalidData), a cancellation to 
+    /// [Interrupted](std::io::ErrorKind::Interrupted), a full buffer or 
+    /// buffer limit to [OutOfMemory](std::io::ErrorKind::OutOfMemory) and 
+    /// poisoning to 
+    /// [Other](std::io::ErrorKind::Other). The message is the 
+    /// error's [Display] text.
+    fn from(err: AnyRecvError) -> Self
+    {
+        use std::io::ErrorKind;
+        let kind = match &err
+        {
+            AnyRecvError::RecvError(_) => ErrorKind::BrokenPipe,
+            AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout) => ErrorKind::TimedOut,
+            AnyRecvError::RecvTimeoutError(_) => ErrorKind::BrokenPipe,
+            AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty) => ErrorKind::WouldBlock,
+            AnyRecvError::TryRecvError(_) => ErrorKind::BrokenPipe,
+            AnyRecvError::WrongType(..) => ErrorKind::InvalidData,
+            AnyRecvError::Cancelled => ErrorKind::Interrupted,
+            AnyRecvError::Poisoned => ErrorKind::Other,
+            #[cfg(feature = "buf_recv")]
+            AnyRecvError::BufRecvError(_) => ErrorKind::InvalidData,
+            #[cfg(feature = "buf_recv")]
+            AnyRecvError::EmptyBuffer => ErrorKind::WouldBlock,
+            #[cfg(feature = "buf_recv")]
+            AnyRecvError::BufferFull(_) => ErrorKind::OutOfMemory,
+            #[cfg(feature = "buf_recv")]
+            AnyRecvError::BufferLimitReached(_) => ErrorKind::OutOfMemory,
+        };
+        std::io::Error::new(kind, err.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests 
 {
@@ -143,7 +870,7 @@ mod tests
     pub fn any_channel_test()
     {
         let (atx, arx) = channel();
-        atx.send(67 as i32).unwrap();
+        atx.send(67i32).unwrap();
         println!("{:?}", arx.recv::<u32>());
     }
     
@@ -151,7 +878,7 @@ mod tests
     pub fn any_channel_test_1()
     {
         let (atx, arx) = channel();
-        atx.send(88 as i32).unwrap();
+        atx.send(88i32).unwrap();
         println!("{:?}", arx.recv::<i32>());
     }
     
@@ -214,4 +941,3059 @@ mod tests
         tx.send(String::from("example")).unwrap();
     
     }
+
+    fn is_numeric(type_id: TypeId) -> bool
+    {
+        type_id == TypeId::of::<i32>() || type_id == TypeId::of::<f32>()
+    }
+
+    #[test]
+    pub fn recv_filter_test()
+    {
+        let (tx, rx) = channel();
+        tx.send(5i32).unwrap();
+        tx.send(String::from("log")).unwrap();
+
+        let first = rx.recv_filter(is_numeric).unwrap();
+        assert_eq!(first.downcast_ref::<i32>(), Some(&5));
+
+        // A non-matching value is handed back rather than lost
+        match rx.recv_filter(is_numeric)
+        {
+            Err(AnyRecvError::WrongType(type_id, r)) =>
+            {
+                assert_eq!(type_id, TypeId::of::<String>());
+                assert_eq!(*r.downcast::<String>().unwrap(), "log");
+            },
+            other => panic!("expected WrongType, got {:?}", other)
+        }
+        assert!(matches!(rx.try_recv_filter(is_numeric), Err(AnyRecvError::TryRecvError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffered_recv_filter_test()
+    {
+        let (tx, mut rx) = crate::buffered_channel();
+        tx.send(String::from("log")).unwrap();
+        tx.send(1.5f32).unwrap();
+        tx.send(7i32).unwrap();
+
+        let first = rx.recv_filter_until(is_numeric).unwrap();
+        assert_eq!(first.downcast_ref::<f32>(), Some(&1.5));
+
+        // The String was parked and can be filtered out of the buffer
+        let log = rx.try_recv_filter(|type_id| type_id == TypeId::of::<String>()).unwrap();
+        assert_eq!(log.downcast_ref::<String>().map(String::as_str), Some("log"));
+
+        let second = rx.try_recv_filter(is_numeric).unwrap();
+        assert_eq!(second.downcast_ref::<i32>(), Some(&7));
+        assert!(matches!(rx.try_recv_filter(is_numeric), Err(AnyRecvError::TryRecvError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn flush_channel_to_buffer_test()
+    {
+        let (tx, mut rx) = crate::buffered_channel();
+        tx.send(1u8).unwrap();
+        tx.send(2u16).unwrap();
+        tx.send(3u32).unwrap();
+        tx.send(4u8).unwrap();
+        tx.send(String::from("five")).unwrap();
+
+        assert_eq!(rx.flush_channel_to_buffer(), 5);
+        assert_eq!(rx.buffer_len(), 5);
+        assert_eq!(rx.recv_buf::<u8>().unwrap(), 1);
+        assert_eq!(rx.recv_buf::<u8>().unwrap(), 4);
+        assert_eq!(rx.buffer_len(), 3);
+
+        drop(tx);
+        assert_eq!(rx.flush_channel_to_buffer(), 0);
+    }
+
+    #[test]
+    pub fn shared_receiver_test()
+    {
+        use std::thread;
+        let (tx, rx) = shared_channel();
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| 
+            {
+                let rx = rx.clone();
+                thread::spawn(move || 
+                {
+                    let mut received = Vec::new();
+                    while let Ok(n) = rx.recv::<u32>()
+                    {
+                        received.push(n);
+                    }
+                    received
+                })
+            })
+            .collect();
+
+        for n in 0..1000u32
+        {
+            tx.send(n).unwrap();
+        }
+        drop(tx);
+
+        let mut received: Vec<u32> = workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect();
+        received.sort_unstable();
+        assert_eq!(received, (0..1000).collect::<Vec<_>>());
+        assert!(matches!(rx.try_recv::<u32>(), Err(AnyRecvError::TryRecvError(_))));
+        assert!(matches!(rx.recv_any(), Err(AnyRecvError::RecvError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn shared_buffered_receiver_test()
+    {
+        use std::{thread, time::Duration};
+        const COUNT: u32 = 10_000;
+        let (tx, rx) = crate::buffered_channel();
+        let rx = rx.into_shared();
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| 
+            {
+                let rx = rx.clone();
+                thread::spawn(move || 
+                {
+                    let (mut numbers, mut strings) = (Vec::new(), Vec::new());
+                    loop
+                    {
+                        let disconnected = match rx.recv::<u32>()
+                        {
+                            Ok(n) => { numbers.push(n); false },
+                            Err(AnyRecvError::BufRecvError(_)) => false,
+                            Err(_) => true
+                        };
+                        // Strings parked by any worker are visible to all of them
+                        while let Ok(s) = rx.recv_buf::<String>()
+                        {
+                            strings.push(s);
+                        }
+                        if disconnected
+                        {
+                            break (numbers, strings);
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for n in 0..COUNT
+        {
+            match n % 2
+            {
+                0 => tx.send(n).unwrap(),
+                _ => tx.send(n.to_string()).unwrap()
+            }
+        }
+        drop(tx);
+
+        let (mut numbers, mut strings) = (Vec::new(), Vec::new());
+        for worker in workers
+        {
+            let (n, s) = worker.join().unwrap();
+            numbers.extend(n);
+            strings.extend(s.into_iter().map(|s| s.parse::<u32>().unwrap()));
+        }
+        numbers.sort_unstable();
+        strings.sort_unstable();
+        assert_eq!(numbers, (0..COUNT).step_by(2).collect::<Vec<_>>());
+        assert_eq!(strings, (1..COUNT).step_by(2).collect::<Vec<_>>());
+        assert!(matches!(rx.recv_timeout::<u32>(Duration::from_millis(1)), Err(AnyRecvError::RecvTimeoutError(_))));
+    }
+
+    #[test]
+    pub fn recv_map_test()
+    {
+        let (tx, rx) = channel();
+        tx.send(42u32).unwrap();
+        tx.send(1.5f32).unwrap();
+        assert_eq!(rx.recv_map(|n: u32| n.to_string()).unwrap(), "42");
+        match rx.recv_map(|n: u32| n.to_string())
+        {
+            Err(AnyRecvError::WrongType(type_id, r)) =>
+            {
+                assert_eq!(type_id, TypeId::of::<f32>());
+                assert_eq!(*r.downcast::<f32>().unwrap(), 1.5);
+            },
+            other => panic!("expected WrongType, got {:?}", other)
+        }
+    }
+
+    #[test]
+    pub fn dispatcher_test()
+    {
+        use std::{cell::RefCell, rc::Rc};
+        let numbers = Rc::new(RefCell::new(Vec::new()));
+        let strings = Rc::new(RefCell::new(Vec::new()));
+
+        let mut dispatcher = Dispatcher::new();
+        let sink = numbers.clone();
+        dispatcher.register(move |n: u32| sink.borrow_mut().push(n));
+        let sink = strings.clone();
+        dispatcher.register(move |s: String| sink.borrow_mut().push(s));
+
+        let (tx, rx) = channel();
+        tx.send(1u32).unwrap();
+        tx.send(String::from("a")).unwrap();
+        tx.send(2u32).unwrap();
+        drop(tx);
+        assert!(dispatcher.run(&rx).is_ok());
+        assert_eq!(*numbers.borrow(), vec![1, 2]);
+        assert_eq!(*strings.borrow(), vec![String::from("a")]);
+
+        // Values without a handler are handed back
+        let unhandled = dispatcher.dispatch(Box::new(0.5f32)).unwrap_err();
+        assert_eq!(*unhandled.downcast::<f32>().unwrap(), 0.5);
+    }
+
+    #[test]
+    #[cfg(feature = "flume")]
+    pub fn flume_bounded_channel_test()
+    {
+        use std::{thread, time::Duration};
+        let (tx, rx) = flume_bounded_channel(1);
+        tx.send(1u8).unwrap();
+        let sender = thread::spawn(move || tx.send(2u8).unwrap());
+        thread::sleep(Duration::from_millis(10));
+        assert_eq!(rx.recv::<u8>().unwrap(), 1);
+        assert_eq!(rx.recv::<u8>().unwrap(), 2);
+        sender.join().unwrap();
+        assert!(matches!(rx.recv::<u8>(), Err(AnyRecvError::RecvError(_))));
+    }
+
+    #[cfg(feature = "async")]
+    fn block_on<F: std::future::Future>(future: F) -> F::Output
+    {
+        use std::{sync::Arc, task::{Context, Poll}, thread};
+        struct ThreadWaker(thread::Thread);
+        impl std::task::Wake for ThreadWaker
+        {
+            fn wake(self: Arc<Self>)
+            {
+                self.0.unpark();
+            }
+        }
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop
+        {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx)
+            {
+                break output;
+            }
+            thread::park();
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    pub fn async_flume_test()
+    {
+        use futures_core::Stream;
+        use std::{future::poll_fn, pin::Pin};
+        let (tx, rx) = flume_channel();
+        let token = CancelToken::new(&tx);
+        token.cancel();
+        block_on(tx.send_async(5u32)).unwrap();
+        tx.send(String::from("sync")).unwrap();
+
+        // Sync and async use of the same channel, with the wake message skipped
+        assert_eq!(block_on(rx.recv_async::<u32>()).unwrap(), 5);
+        let mut stream = rx.into_stream();
+        drop((tx, token));
+        let r = block_on(poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))).unwrap();
+        assert_eq!(*r.downcast::<String>().unwrap(), "sync");
+        assert!(block_on(poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))).is_none());
+    }
+
+    #[test]
+    pub fn drain_test()
+    {
+        // Live sender: stops at Empty
+        let (tx, rx) = channel();
+        tx.send(1i32).unwrap();
+        tx.send("straggler").unwrap();
+        tx.send(2i32).unwrap();
+        let (ints, rest) = rx.drain_of::<i32>();
+        assert_eq!(ints, vec![1, 2]);
+        assert_eq!(rest.len(), 1);
+        assert_eq!(*rest[0].downcast_ref::<&str>().unwrap(), "straggler");
+        tx.send(3i32).unwrap_err();
+
+        // Dead sender: stops at Disconnected
+        let (tx, rx) = channel();
+        tx.send(1i32).unwrap();
+        tx.send(2u8).unwrap();
+        drop(tx);
+        let values = rx.drain();
+        assert_eq!(values.len(), 2);
+        assert_eq!(*values[0].downcast_ref::<i32>().unwrap(), 1);
+        assert_eq!(*values[1].downcast_ref::<u8>().unwrap(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffered_drain_test()
+    {
+        // Live sender: stops at Empty
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1i32).unwrap();
+        tx.send(2i32).unwrap();
+        tx.send(3u8).unwrap();
+        rx.recv::<u8>().unwrap_err();
+        rx.recv::<u8>().unwrap_err();
+        tx.send(4i32).unwrap();
+        tx.send("straggler").unwrap();
+        let (ints, rest) = rx.drain_of::<i32>();
+        assert_eq!(ints, vec![1, 2, 4]);
+        assert_eq!(rest.len(), 2);
+        assert_eq!(*rest[0].downcast_ref::<u8>().unwrap(), 3);
+        assert_eq!(*rest[1].downcast_ref::<&str>().unwrap(), "straggler");
+
+        // Dead sender: stops at Disconnected
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1i32).unwrap();
+        tx.send(2u8).unwrap();
+        rx.recv::<u8>().unwrap_err();
+        drop(tx);
+        let values = rx.drain();
+        assert_eq!(values.len(), 2);
+        assert_eq!(*values[0].downcast_ref::<i32>().unwrap(), 1);
+        assert_eq!(*values[1].downcast_ref::<u8>().unwrap(), 2);
+    }
+
+    #[test]
+    pub fn filter_recv_test()
+    {
+        let (tx, rx) = channel();
+        for i in [-3i32, 0, -1, 7, 9].iter()
+        {
+            tx.send(*i).unwrap();
+        }
+        assert_eq!(rx.filter_recv(|i: &i32| *i > 0).unwrap(), 7);
+        assert_eq!(rx.try_recv::<i32>().unwrap(), 9);
+        tx.send('x').unwrap();
+        assert!(matches!(rx.filter_recv(|i: &i32| *i > 0), Err(AnyRecvError::WrongType(..))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffered_filter_recv_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        for i in [-3i32, 0, -1, 7, 9].iter()
+        {
+            tx.send(*i).unwrap();
+        }
+        assert_eq!(rx.filter_recv(|i: &i32| *i > 0).unwrap(), 7);
+        assert_eq!(rx.buffer_len(), 3);
+        assert_eq!(rx.filter_recv(|i: &i32| *i == 0).unwrap(), 0);
+        assert_eq!(rx.recv_buf::<i32>().unwrap(), -3);
+        assert_eq!(rx.recv_buf::<i32>().unwrap(), -1);
+        assert_eq!(rx.recv::<i32>().unwrap(), 9);
+        tx.send('x').unwrap();
+        assert!(matches!(rx.filter_recv(|i: &i32| *i > 0), Err(AnyRecvError::BufRecvError(_))));
+        assert_eq!(rx.recv_buf::<char>().unwrap(), 'x');
+    }
+
+    #[test]
+    #[cfg(all(feature = "buf_recv", feature = "metrics"))]
+    pub fn recv_counts_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1i32).unwrap();
+        tx.send('a').unwrap();
+        tx.send(2i32).unwrap();
+        tx.send(String::from("b")).unwrap();
+        tx.send(3i32).unwrap();
+        rx.recv::<i32>().unwrap();
+        rx.recv::<i32>().unwrap_err();
+        rx.recv::<i32>().unwrap();
+        rx.recv_buf::<char>().unwrap();
+        rx.recv_filter(|type_id| type_id == TypeId::of::<String>()).unwrap();
+        rx.try_recv::<i32>().unwrap();
+        rx.recv_buf::<char>().unwrap_err();
+        assert_eq!(rx.recv_counts().len(), 3);
+        assert_eq!(rx.recv_counts()[&TypeId::of::<i32>()], 3);
+        assert_eq!(rx.recv_counts()[&TypeId::of::<char>()], 1);
+        assert_eq!(rx.recv_counts()[&TypeId::of::<String>()], 1);
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn recv_n_test()
+    {
+        use std::{thread, time::Duration};
+        let (tx, mut rx) = buffered_channel();
+        assert!(rx.recv_n::<u32>(0, Duration::from_secs(0)).unwrap().is_empty());
+
+        tx.send(1u32).unwrap();
+        tx.send("other").unwrap();
+        tx.send(2u32).unwrap();
+        tx.send(3u32).unwrap();
+        assert_eq!(rx.recv_n::<u32>(2, Duration::from_secs(1)).unwrap(), vec![1, 2]);
+        assert_eq!(rx.buffer_len(), 1);
+
+        // The deadline covers the whole batch, even while values keep arriving
+        let sender = thread::spawn(move ||
+        {
+            for i in 4..10u32
+            {
+                thread::sleep(Duration::from_millis(20));
+                if tx.send(i).is_err()
+                {
+                    break;
+                }
+            }
+        });
+        let (partial, err) = rx.recv_n::<u32>(10, Duration::from_millis(50)).unwrap_err();
+        assert!(matches!(err, AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout)));
+        assert_eq!(partial[0], 3);
+        assert!(partial.len() < 5);
+        drop(rx);
+        sender.join().unwrap();
+    }
+
+    #[test]
+    pub fn recv_either_test()
+    {
+        let (tx, rx) = channel();
+        tx.send(1i32).unwrap();
+        tx.send("two").unwrap();
+        tx.send(3i32).unwrap();
+        tx.send(4u8).unwrap();
+        assert_eq!(rx.recv_either::<i32, &str>().unwrap(), Either::Left(1));
+        assert_eq!(rx.recv_either::<i32, &str>().unwrap(), Either::Right("two"));
+        assert_eq!(rx.recv_either::<i32, &str>().unwrap(), Either::Left(3));
+        assert!(matches!(rx.recv_either::<i32, &str>(), Err(AnyRecvError::WrongType(type_id, _)) if type_id == TypeId::of::<u8>()));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffered_recv_either_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1i32).unwrap();
+        tx.send(2u8).unwrap();
+        tx.send("three").unwrap();
+        rx.recv::<u8>().unwrap_err();
+        assert_eq!(rx.recv_either::<i32, &str>().unwrap(), Either::Left(1));
+        assert_eq!(rx.recv_either::<u8, &str>().unwrap(), Either::Left(2));
+        tx.send('x').unwrap();
+        assert!(matches!(rx.recv_either::<i32, u8>(), Err(AnyRecvError::BufRecvError(_))));
+        assert_eq!(rx.recv_either::<i32, &str>().unwrap(), Either::Right("three"));
+        assert_eq!(rx.recv_either::<i32, char>().unwrap(), Either::Right('x'));
+    }
+
+    #[test]
+    #[cfg(all(feature = "tracing", feature = "buf_recv"))]
+    pub fn tracing_test()
+    {
+        use std::{fmt, sync::{Arc, Mutex}};
+        use tracing::{field::{Field, Visit}, span, Event, Level, Metadata, Subscriber};
+
+        #[derive(Default)]
+        struct Collector(Arc<Mutex<Vec<(Level, String)>>>);
+        struct Message<'a>(&'a mut String);
+        impl Visit for Message<'_>
+        {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug)
+            {
+                if field.name() == "message"
+                {
+                    *self.0 = format!("{:?}", value);
+                }
+            }
+        }
+        impl Subscriber for Collector
+        {
+            fn enabled(&self, _: &Metadata<'_>) -> bool { true }
+            fn new_span(&self, _: &span::Attributes<'_>) -> span::Id { span::Id::from_u64(1) }
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, event: &Event<'_>)
+            {
+                let mut message = String::new();
+                event.record(&mut Message(&mut message));
+                self.0.lock().unwrap().push((*event.metadata().level(), message));
+            }
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let collector = Collector::default();
+        let events = collector.0.clone();
+        tracing::subscriber::with_default(collector, ||
+        {
+            let (tx, mut rx) = buffered_channel();
+            tx.send(1i32).unwrap();
+            tx.send('a').unwrap();
+            rx.recv::<i32>().unwrap();
+            rx.recv::<i32>().unwrap_err();
+            rx.recv_buf::<u8>().unwrap_err();
+        });
+        let events = events.lock().unwrap();
+        let count = |level, message: &str| events.iter().filter(|e| e.0 == level && e.1 == message).count();
+        assert_eq!(count(Level::TRACE, "send"), 2);
+        assert_eq!(count(Level::TRACE, "recv"), 1);
+        assert_eq!(count(Level::TRACE, "recv failed"), 2);
+        assert_eq!(count(Level::DEBUG, "buffered value of unrequested type"), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn recv_while_test()
+    {
+        use std::time::Duration;
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1i32).unwrap();
+        tx.send(2i32).unwrap();
+        tx.send("other").unwrap();
+        tx.send(3i32).unwrap();
+        tx.send(10i32).unwrap();
+        tx.send(4i32).unwrap();
+        assert_eq!(rx.try_recv_while(|i: &i32| *i < 5), vec![1, 2, 3]);
+
+        // The failing value is pushed back and is the next i32 received
+        assert_eq!(rx.buffer_len(), 2);
+        assert_eq!(rx.try_recv_while(|i: &i32| *i < 5), vec![]);
+        assert_eq!(rx.recv::<i32>().unwrap(), 10);
+        assert_eq!(rx.recv_buf::<&str>().unwrap(), "other");
+
+        // Buffered values are checked before the channel
+        tx.send(6i32).unwrap();
+        tx.send(5u8).unwrap();
+        rx.recv::<u8>().unwrap_err();
+        rx.recv::<u8>().unwrap_err();
+        assert_eq!(rx.recv_while_timeout(|i: &i32| *i < 10, Duration::from_millis(20)), vec![4, 6]);
+        assert_eq!(rx.recv_buf::<u8>().unwrap(), 5);
+
+        tx.send(7i32).unwrap();
+        tx.send(11i32).unwrap();
+        assert_eq!(rx.recv_while(|i: &i32| *i < 10), vec![7]);
+        drop(tx);
+        assert_eq!(rx.recv_while(|i: &i32| *i > 10), vec![11]);
+    }
+
+    #[test]
+    pub fn any_dispatch_test()
+    {
+        let (tx, rx) = channel();
+        tx.send(2i32).unwrap();
+        tx.send(String::from("four")).unwrap();
+        tx.send('x').unwrap();
+        tx.send(8u64).unwrap();
+        let describe = |rx: &AnyReceiver| any_dispatch!(rx => {
+            i32 as i => format!("i32 {}", i),
+            String as s => format!("string {}", s),
+            char as c => format!("char {}", c)
+        });
+        assert_eq!(describe(&rx).unwrap(), "i32 2");
+        assert_eq!(describe(&rx).unwrap(), "string four");
+        assert_eq!(describe(&rx).unwrap(), "char x");
+        assert!(matches!(describe(&rx), Err(AnyRecvError::WrongType(..))));
+        drop(tx);
+        assert!(matches!(describe(&rx), Err(AnyRecvError::RecvError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffered_any_dispatch_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        tx.send(2i32).unwrap();
+        tx.send(3u8).unwrap();
+        rx.recv::<u8>().unwrap_err();
+        let mut total = 0;
+        for _ in 0..2
+        {
+            any_dispatch!(rx => {
+                u8 as i => total += i as i32,
+                i32 as i => total += i,
+                _ => total += 100
+            }).unwrap();
+        }
+        assert_eq!(total, 5);
+        tx.send("other").unwrap();
+        assert_eq!(any_dispatch!(rx => { i32 as _i => 1, _ => 0 }).unwrap(), 0);
+    }
+
+    #[test]
+    pub fn chain_test()
+    {
+        let (tx1, rx1) = channel();
+        let (tx2, rx2) = channel();
+        tx1.send(1i32).unwrap();
+        tx2.send(3i32).unwrap();
+        tx1.send(2i32).unwrap();
+        let rx = rx1.chain(rx2);
+
+        // The first receiver is read until it is both drained and disconnected
+        assert_eq!(rx.recv::<i32>().unwrap(), 1);
+        assert_eq!(rx.recv::<i32>().unwrap(), 2);
+        let err = rx.try_recv::<i32>().unwrap_err();
+        assert_eq!(err.index, 0);
+        assert!(matches!(err.error, AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty)));
+        drop(tx1);
+        assert_eq!(rx.recv::<i32>().unwrap(), 3);
+        assert_eq!(rx.current(), 1);
+
+        tx2.send('x').unwrap();
+        let err = rx.recv::<i32>().unwrap_err();
+        assert_eq!(err.index, 1);
+        assert!(matches!(err.error, AnyRecvError::WrongType(..)));
+        drop(tx2);
+        let err = rx.recv_timeout::<i32>(std::time::Duration::from_millis(10)).unwrap_err();
+        assert_eq!(err.index, 1);
+        assert!(matches!(err.error, AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Disconnected)));
+    }
+
+    #[test]
+    pub fn chain_fold_test()
+    {
+        let (senders, mut receivers): (Vec<_>, Vec<_>) = (0..3).map(|_| channel()).unzip();
+        for (i, tx) in senders.into_iter().enumerate()
+        {
+            tx.send(i).unwrap();
+        }
+        let first = ChainedAnyReceiver::from(receivers.remove(0));
+        let rx = receivers.into_iter().fold(first, ChainedAnyReceiver::chain);
+        assert_eq!(rx.receivers.len(), 3);
+        let received: Vec<usize> = (0..3).map(|_| rx.recv().unwrap()).collect();
+        assert_eq!(received, vec![0, 1, 2]);
+        assert_eq!(rx.try_recv_any().unwrap_err().index, 2);
+    }
+
+    #[test]
+    pub fn from_receiver_test()
+    {
+        let (tx, rx) = mpsc::channel();
+        let tx = AnySender(tx);
+        let rx: AnyReceiver = rx.into();
+        tx.send(1u8).unwrap();
+        assert_eq!(rx.recv::<u8>(), Ok(1));
+    }
+
+    #[test]
+    pub fn is_disconnected_test()
+    {
+        let (tx, rx) = channel();
+        assert!(!rx.is_disconnected());
+
+        // Probing neither loses nor reorders values
+        tx.send(1i32).unwrap();
+        tx.send(2i32).unwrap();
+        assert!(!rx.is_disconnected());
+        drop(tx);
+        assert!(!rx.is_disconnected());
+        assert_eq!(rx.recv::<i32>().unwrap(), 1);
+        assert!(!rx.is_disconnected());
+        assert_eq!(rx.try_recv::<i32>().unwrap(), 2);
+        assert!(rx.is_disconnected());
+    }
+
+    #[cfg(feature = "flume")]
+    #[test]
+    pub fn sender_is_disconnected_test()
+    {
+        let (tx, rx) = channel();
+        assert!(!tx.is_disconnected());
+        tx.send(1i32).unwrap();
+        assert!(!tx.is_disconnected());
+        assert_eq!(rx.try_recv::<i32>(), Ok(1));
+        assert_eq!(rx.try_recv::<i32>(), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty)));
+        drop(rx);
+        assert!(tx.is_disconnected());
+    }
+
+    #[test]
+    pub fn merge_test()
+    {
+        let (chatty_tx, chatty_rx) = channel();
+        let (quiet_tx, quiet_rx) = channel();
+        for i in 0..10
+        {
+            chatty_tx.send(i).unwrap();
+        }
+        quiet_tx.send(String::from("quiet")).unwrap();
+        quiet_tx.send(String::from("quieter")).unwrap();
+        let rx = merge(vec![chatty_rx, quiet_rx]);
+
+        // Ready sources take turns, so the chatty one cannot starve the other
+        assert_eq!(rx.recv::<i32>().unwrap(), 0);
+        assert_eq!(rx.recv::<String>().unwrap(), "quiet");
+        assert_eq!(rx.recv::<i32>().unwrap(), 1);
+        assert_eq!(rx.try_recv::<String>().unwrap(), "quieter");
+        assert_eq!(rx.recv_timeout::<i32>(std::time::Duration::from_millis(10)).unwrap(), 2);
+        assert_eq!(rx.recv::<i32>().unwrap(), 3);
+        assert!(matches!(rx.recv::<String>(), Err(AnyRecvError::WrongType(..))));
+
+        drop(quiet_tx);
+        let rest: Vec<i32> = (5..10).map(|_| rx.recv().unwrap()).collect();
+        assert_eq!(rest, vec![5, 6, 7, 8, 9]);
+        assert!(matches!(rx.try_recv_any(), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty))));
+        drop(chatty_tx);
+        assert!(matches!(rx.recv_any(), Err(AnyRecvError::RecvError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn recv_or_return_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1i32).unwrap();
+        tx.send(String::from("two")).unwrap();
+        tx.send(3u8).unwrap();
+        rx.recv::<u8>().unwrap_err();
+        assert_eq!(rx.recv_or_return::<i32>().unwrap(), 1);
+        match rx.recv_or_return::<i32>()
+        {
+            Err(AnyRecvError::WrongType(type_id, r)) =>
+            {
+                assert_eq!(type_id, TypeId::of::<String>());
+                assert_eq!(*r.downcast::<String>().unwrap(), "two");
+            },
+            other => panic!("expected WrongType, got {:?}", other)
+        }
+        assert_eq!(rx.buffer_len(), 0);
+        assert_eq!(rx.recv_or_return::<u8>().unwrap(), 3);
+    }
+
+    #[test]
+    pub fn recv_timeout_or_default_test()
+    {
+        use std::time::{Duration, Instant};
+        let (tx, rx) = channel();
+        tx.send(7u32).unwrap();
+        tx.send("wrong").unwrap();
+        assert_eq!(rx.recv_timeout_or_default::<u32>(Duration::from_millis(10)), 7);
+        assert_eq!(rx.recv_timeout_or_default::<u32>(Duration::from_millis(10)), 0);
+        assert_eq!(rx.recv_deadline_or_default::<u32>(Instant::now() + Duration::from_millis(10)), 0);
+        tx.send(8u32).unwrap();
+        drop(tx);
+        assert_eq!(rx.recv_deadline_or_default::<u32>(Instant::now()), 8);
+        assert_eq!(rx.recv_timeout_or_default::<String>(Duration::from_secs(10)), "");
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffered_recv_timeout_or_default_test()
+    {
+        use std::time::{Duration, Instant};
+        let (tx, mut rx) = buffered_channel();
+        tx.send("other").unwrap();
+        tx.send(7u32).unwrap();
+        assert_eq!(rx.recv_timeout_or_default::<u32>(Duration::from_millis(10)), 7);
+        assert_eq!(rx.recv_deadline_or_default::<u32>(Instant::now() + Duration::from_millis(10)), 0);
+        assert_eq!(rx.recv_timeout_or_default::<&str>(Duration::from_millis(10)), "other");
+        drop(tx);
+        assert_eq!(rx.recv_timeout_or_default::<u32>(Duration::from_secs(10)), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffered_channel_with_capacity_test()
+    {
+        let (tx, mut rx) = buffered_channel_with_capacity(4);
+        assert!(rx.buf.capacity() >= 4);
+        for i in 0..1000u32
+        {
+            tx.send(i).unwrap();
+            tx.send(i as u8).unwrap();
+            tx.send(i.to_string()).unwrap();
+        }
+        tx.send('x').unwrap();
+        assert_eq!(rx.recv_until::<char>().unwrap(), 'x');
+        assert_eq!(rx.buffer_len(), 3000);
+        rx.reserve(16);
+        assert!(rx.buf.capacity() >= 16);
+        for i in 0..1000u32
+        {
+            assert_eq!(rx.recv_buf::<String>().unwrap(), i.to_string());
+            assert_eq!(rx.recv_buf::<u32>().unwrap(), i);
+            assert_eq!(rx.recv_buf::<u8>().unwrap(), i as u8);
+        }
+        assert!(rx.buf.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn recv_inspect_test()
+    {
+        use std::time::Duration;
+        let (tx, mut rx) = buffered_channel();
+        tx.send("other").unwrap();
+        tx.send(1i32).unwrap();
+        tx.send(2i32).unwrap();
+        assert_eq!(rx.recv_inspect(|i: &i32| *i * 10).unwrap(), 10);
+        assert_eq!(rx.recv_inspect(|i: &i32| *i * 10).unwrap(), 10);
+        assert_eq!(rx.recv::<i32>().unwrap(), 1);
+
+        // A buffered value is inspected in place, keeping its order
+        rx.recv::<u8>().unwrap_err();
+        assert_eq!(rx.try_recv_inspect(|i: &i32| *i).unwrap(), 2);
+        assert_eq!(rx.recv::<i32>().unwrap(), 2);
+        assert!(matches!(rx.try_recv_inspect(|i: &i32| *i), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty))));
+        assert!(matches!(
+            rx.recv_timeout_inspect(|i: &i32| *i, Duration::from_millis(10)),
+            Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout))));
+        assert_eq!(rx.recv_buf::<&str>().unwrap(), "other");
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn into_buffered_test()
+    {
+        let (tx, rx) = channel();
+        tx.send(1i32).unwrap();
+        tx.send('a').unwrap();
+        tx.send(2i32).unwrap();
+        assert!(!rx.is_disconnected());
+        let mut rx = rx.into_buffered();
+        assert_eq!(rx.recv_until::<char>().unwrap(), 'a');
+        assert_eq!(rx.buffer_len(), 1);
+
+        let (rx, buffered) = rx.into_plain();
+        assert_eq!(buffered.len(), 1);
+        assert_eq!(*buffered[0].downcast_ref::<i32>().unwrap(), 1);
+        assert_eq!(rx.recv::<i32>().unwrap(), 2);
+        tx.send(3i32).unwrap();
+        let mut rx = rx.into_buffered();
+        assert_eq!(rx.recv::<i32>().unwrap(), 3);
+    }
+
+    #[test]
+    pub fn io_error_test()
+    {
+        use std::{io::{Error, ErrorKind}, time::Duration};
+        let (tx, rx) = channel();
+        assert_eq!(Error::from(rx.try_recv::<i32>().unwrap_err()).kind(), ErrorKind::WouldBlock);
+        assert_eq!(Error::from(rx.recv_timeout::<i32>(Duration::from_millis(1)).unwrap_err()).kind(), ErrorKind::TimedOut);
+        register_type_name::<&str>();
+        tx.send("text").unwrap();
+        let err = Error::from(rx.recv::<i32>().unwrap_err());
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "Received wrong type: &str");
+        assert_eq!(Error::from(AnyRecvError::Cancelled).kind(), ErrorKind::Interrupted);
+        drop(tx);
+        assert_eq!(Error::from(rx.recv::<i32>().unwrap_err()).kind(), ErrorKind::BrokenPipe);
+        assert_eq!(Error::from(rx.try_recv::<i32>().unwrap_err()).kind(), ErrorKind::BrokenPipe);
+        assert_eq!(Error::from(rx.recv_timeout::<i32>(Duration::from_millis(1)).unwrap_err()).kind(), ErrorKind::BrokenPipe);
+
+        // Usable with ? in functions returning io::Result
+        fn read(rx: &AnyReceiver) -> std::io::Result<i32>
+        {
+            Ok(rx.try_recv::<i32>()?)
+        }
+        assert_eq!(read(&rx).unwrap_err().kind(), ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffered_io_error_test()
+    {
+        use std::io::{Error, ErrorKind};
+        let (tx, mut rx) = buffered_channel();
+        assert_eq!(Error::from(rx.recv_buf::<i32>().unwrap_err()).kind(), ErrorKind::WouldBlock);
+        tx.send('a').unwrap();
+        assert_eq!(Error::from(rx.recv::<i32>().unwrap_err()).kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    pub fn ready_test()
+    {
+        let (tx, rx) = channel();
+        assert_eq!(rx.ready(), None);
+        tx.send(1i32).unwrap();
+        tx.send('a').unwrap();
+        assert_eq!(rx.ready(), Some(TypeId::of::<i32>()));
+        assert!(rx.ready_is::<i32>());
+        assert!(!rx.ready_is::<char>());
+
+        // The probed value is still the next one delivered
+        assert_eq!(rx.recv::<i32>().unwrap(), 1);
+        assert!(rx.ready_is::<char>());
+        assert_eq!(rx.try_recv::<char>().unwrap(), 'a');
+        assert_eq!(rx.ready(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffered_ready_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        assert_eq!(rx.ready(), None);
+        tx.send(1i32).unwrap();
+        tx.send('a').unwrap();
+        assert!(!rx.buffer_has::<i32>());
+        assert_eq!(rx.ready(), Some(TypeId::of::<i32>()));
+        assert!(rx.buffer_has::<i32>());
+        assert!(rx.ready_is::<i32>());
+        assert!(rx.ready_is::<char>());
+        assert!(!rx.ready_is::<u8>());
+
+        // Probed values are still delivered
+        assert_eq!(rx.recv::<char>().unwrap(), 'a');
+        assert_eq!(rx.recv::<i32>().unwrap(), 1);
+        assert_eq!(rx.ready(), None);
+    }
+
+    #[test]
+    pub fn recv_error_eq_test()
+    {
+        use std::{collections::HashSet, time::Duration};
+        let (tx, rx) = channel();
+        assert_eq!(rx.try_recv::<i32>(), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty)));
+        assert_eq!(
+            rx.recv_timeout::<i32>(Duration::from_millis(1)),
+            Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout)));
+        tx.send(1u8).unwrap();
+        tx.send(2u8).unwrap();
+        tx.send(3u16).unwrap();
+        let a = rx.recv::<i32>().unwrap_err();
+        let b = rx.recv::<i32>().unwrap_err();
+        let c = rx.recv::<i32>().unwrap_err();
+        assert_eq!(a, AnyRecvError::wrong_type(Box::new(0u8)));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(AnyRecvError::Cancelled, AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty));
+        let set: HashSet<AnyRecvError> = vec![a, b, c, AnyRecvError::Cancelled].into_iter().collect();
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffered_recv_error_eq_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        assert_eq!(rx.recv_buf::<i32>(), Err(AnyRecvError::EmptyBuffer));
+        tx.send('a').unwrap();
+        assert_eq!(rx.recv::<i32>(), Err(AnyRecvError::BufRecvError(TypeId::of::<char>())));
+        assert_ne!(AnyRecvError::BufRecvError(TypeId::of::<char>()), AnyRecvError::BufRecvError(TypeId::of::<i32>()));
+        assert_ne!(AnyRecvError::EmptyBuffer, AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty));
+        drop(tx);
+        assert_eq!(
+            rx.recv_timeout::<i32>(std::time::Duration::from_millis(1)),
+            Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Disconnected)));
+        assert_ne!(
+            AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Disconnected),
+            AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout));
+    }
+
+    #[test]
+    pub fn wrong_type_test()
+    {
+        use std::time::Duration;
+        let (tx, rx) = channel();
+        let check = |result: Result<i32, AnyRecvError>, expected: &str| match result
+        {
+            Err(AnyRecvError::WrongType(type_id, r)) =>
+            {
+                assert_eq!(type_id, TypeId::of::<String>());
+                assert_eq!(*r.downcast::<String>().unwrap(), expected);
+            },
+            other => panic!("expected WrongType, got {:?}", other)
+        };
+        for s in ["recv", "recv_timeout", "try_recv"].iter()
+        {
+            tx.send(s.to_string()).unwrap();
+        }
+        check(rx.recv(), "recv");
+        check(rx.recv_timeout(Duration::from_millis(10)), "recv_timeout");
+        check(rx.try_recv(), "try_recv");
+    }
+
+    #[test]
+    pub fn into_typed_test()
+    {
+        use std::time::Duration;
+        let (tx, rx) = channel();
+        let (typed, router) = rx.into_typed::<i32>(Fallback::Drop);
+        tx.send(1i32).unwrap();
+        tx.send("dropped").unwrap();
+        tx.send(2i32).unwrap();
+        assert_eq!(typed.recv().unwrap(), 1);
+        assert_eq!(typed.recv().unwrap(), 2);
+
+        // Stopping hands back the receiver with nothing lost
+        router.stop();
+        while !router.is_finished()
+        {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        tx.send(3i32).unwrap();
+        let rx = router.join().unwrap();
+        assert_eq!(rx.recv::<i32>().unwrap(), 3);
+        assert!(typed.recv().is_err());
+    }
+
+    #[test]
+    pub fn into_typed_forward_test()
+    {
+        let (tx, rx) = channel();
+        let (other_tx, other_rx) = channel();
+        let (typed, router) = rx.into_typed::<i32>(Fallback::Forward(other_tx));
+        tx.send(String::from("forwarded")).unwrap();
+        tx.send(1i32).unwrap();
+        assert_eq!(typed.recv().unwrap(), 1);
+        assert_eq!(other_rx.recv::<String>().unwrap(), "forwarded");
+        drop(tx);
+        router.join().unwrap();
+        assert!(other_rx.recv::<String>().is_err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    pub fn into_typed_debug_panic_test()
+    {
+        let (tx, rx) = channel();
+        let (typed, router) = rx.into_typed::<i32>(Fallback::DebugPanic);
+        tx.send('x').unwrap();
+        assert!(typed.recv().is_err());
+        assert!(router.join().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn into_unbuffered_test()
+    {
+        let (tx, rx) = channel();
+        let mut rx = rx.into_buffered();
+        tx.send('a').unwrap();
+        tx.send(1i32).unwrap();
+        rx.recv::<i32>().unwrap_err();
+        let mut rx = rx.into_unbuffered().unwrap_err();
+        assert_eq!(rx.recv_buf::<char>().unwrap(), 'a');
+        let rx = rx.into_unbuffered().unwrap();
+        assert_eq!(rx.recv::<i32>().unwrap(), 1);
+    }
+
+    #[test]
+    pub fn received_type_name_test()
+    {
+        let (tx, rx) = channel();
+        register_type_name::<String>();
+        tx.send(String::from("not a number")).unwrap();
+        let err = rx.recv::<u64>().unwrap_err();
+        assert_eq!(err.received_type_name(), Some(std::any::type_name::<String>()));
+        assert_eq!(err.to_string(), format!("Received wrong type: {}", std::any::type_name::<String>()));
+
+        // Types that were never registered have no recorded name
+        struct Unnamed;
+        tx.0.send(Box::new(Unnamed)).unwrap();
+        let err = rx.recv::<u64>().unwrap_err();
+        assert_eq!(err.received_type_name(), None);
+        assert_eq!(err.to_string(), "Received wrong type");
+    }
+
+    #[cfg(feature = "type_names")]
+    #[test]
+    pub fn send_records_type_name_test()
+    {
+        struct Sent;
+        let (tx, rx) = channel();
+        tx.send(Sent).unwrap();
+        let err = rx.recv::<u64>().unwrap_err();
+        assert_eq!(err.received_type_name(), Some(std::any::type_name::<Sent>()));
+    }
+
+    #[test]
+    pub fn select_test()
+    {
+        use std::{thread, time::Duration};
+        let (tx0, rx0) = channel();
+        let (tx1, rx1) = channel();
+        let (_tx2, rx2) = channel();
+        for n in 0..4u32
+        {
+            tx0.send(n).unwrap();
+            tx1.send(n).unwrap();
+        }
+
+        // Ready receivers are served round-robin; the empty one is skipped
+        let mut selector = Select::new(&[&rx0, &rx1, &rx2]);
+        let order: Vec<(usize, u32)> = (0..8).map(|_| selector.select_recv().unwrap()).collect();
+        assert_eq!(order, vec![(0, 0), (1, 0), (0, 1), (1, 1), (0, 2), (1, 2), (0, 3), (1, 3)]);
+        assert!(matches!(selector.try_select(), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty))));
+
+        assert!(matches!(
+            select_timeout(&[&rx0, &rx1], Duration::from_millis(20)), 
+            Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout))
+        ));
+
+        let sender = thread::spawn(move || 
+        {
+            thread::sleep(Duration::from_millis(10));
+            tx1.send(String::from("late")).unwrap();
+        });
+        let (index, r) = select(&[&rx0, &rx1]).unwrap();
+        assert_eq!((index, *r.downcast::<String>().unwrap()), (1, String::from("late")));
+        sender.join().unwrap();
+
+        drop(tx0);
+        assert!(matches!(select(&[&rx0, &rx1]), Err(AnyRecvError::RecvError(_))));
+    }
+
+    #[test]
+    pub fn recv_cancellable_test()
+    {
+        use std::{thread, time::Duration};
+        let (tx, rx) = channel();
+        let token = CancelToken::new(&tx);
+
+        let canceller = token.clone();
+        let handle = thread::spawn(move || 
+        {
+            thread::sleep(Duration::from_millis(20));
+            canceller.cancel();
+        });
+        assert!(matches!(rx.recv_cancellable::<u32>(&token), Err(AnyRecvError::Cancelled)));
+        handle.join().unwrap();
+
+        // Real messages are kept after cancellation and the wake message is 
+        // never observed by ordinary receives
+        tx.send(3u32).unwrap();
+        assert!(matches!(rx.recv_cancellable::<u32>(&token), Err(AnyRecvError::Cancelled)));
+        assert_eq!(rx.recv::<u32>().unwrap(), 3);
+        assert!(matches!(rx.try_recv::<u32>(), Err(AnyRecvError::TryRecvError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn recv_until_cancellable_test()
+    {
+        use std::thread;
+        let (tx, mut rx) = crate::buffered_channel();
+        let token = CancelToken::new(&tx);
+        tx.send(1u8).unwrap();
+        tx.send(String::from("wanted")).unwrap();
+        assert_eq!(rx.recv_until_cancellable::<String>(&token).unwrap(), "wanted");
+
+        let canceller = token.clone();
+        thread::spawn(move || canceller.cancel()).join().unwrap();
+        assert!(matches!(rx.recv_until_cancellable::<String>(&token), Err(AnyRecvError::Cancelled)));
+        assert_eq!(rx.recv_buf::<u8>().unwrap(), 1);
+        assert!(matches!(rx.try_recv::<u8>(), Err(AnyRecvError::TryRecvError(_))));
+
+        // Counted like every other receive; the wake-up message is not
+        #[cfg(feature = "metrics")]
+        {
+            let stats = rx.stats();
+            assert_eq!(stats.received, 2);
+            assert_eq!(rx.recv_counts()[&TypeId::of::<String>()], 1);
+            assert_eq!(rx.recv_counts()[&TypeId::of::<u8>()], 1);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "metrics")]
+    pub fn counted_channel_test()
+    {
+        let (tx, rx) = counted_channel();
+        tx.send(1u8).unwrap();
+        tx.send(2u8).unwrap();
+        tx.send(String::from("unexpected")).unwrap();
+        assert_eq!((tx.sent_count(), rx.in_flight()), (3, 3));
+
+        assert_eq!(rx.recv::<u8>().unwrap(), 1);
+        assert!(matches!(rx.recv::<u8>(), Ok(2)));
+        assert!(matches!(rx.recv::<u8>(), Err(AnyRecvError::WrongType(..))));
+        assert!(matches!(rx.try_recv::<u8>(), Err(AnyRecvError::TryRecvError(_))));
+
+        assert_eq!(tx.recv_count(), 2);
+        assert_eq!(rx.wrong_type_count(), 1);
+        assert_eq!(tx.in_flight(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn raw_channel_test()
+    {
+        use std::{cell::RefCell, collections::VecDeque, time::Duration};
+
+        #[derive(Debug, Default)]
+        struct MockChannel(RefCell<VecDeque<Box<dyn Any>>>);
+
+        impl RawChannel for MockChannel
+        {
+            fn recv(&self) -> Result<Box<dyn Any>, RawRecvError>
+            {
+                self.0.borrow_mut().pop_front().ok_or(RawRecvError::Disconnected)
+            }
+
+            fn try_recv(&self) -> Result<Box<dyn Any>, RawRecvError>
+            {
+                self.0.borrow_mut().pop_front().ok_or(RawRecvError::Disconnected)
+            }
+
+            fn recv_timeout(&self, _: Duration) -> Result<Box<dyn Any>, RawRecvError>
+            {
+                self.0.borrow_mut().pop_front().ok_or(RawRecvError::Disconnected)
+            }
+        }
+
+        let queue = MockChannel::default();
+        queue.0.borrow_mut().extend([Box::new(1u8) as Box<dyn Any>, Box::new(Wake), Box::new("two"), Box::new(3u32)]);
+        let mut rx = BufferedReceiver::new(queue, TypeBuffer::new());
+        assert_eq!(rx.recv_until::<u32>().unwrap(), 3);
+        assert_eq!(rx.buffer_len(), 2);
+        assert_eq!(rx.recv::<&str>().unwrap(), "two");
+        assert_eq!(rx.recv_until::<u8>().unwrap(), 1);
+        assert!(matches!(rx.recv_until::<u8>(), Err(AnyRecvError::RecvError(_))));
+        assert_eq!(rx.try_recv::<u8>(), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Disconnected)));
+    }
+
+    #[test]
+    pub fn recv_dyn_test()
+    {
+        trait Event
+        {
+            fn name(&self) -> String;
+        }
+        struct Click(u32);
+        struct Key(char);
+        impl Event for Click
+        {
+            fn name(&self) -> String { format!("click {}", self.0) }
+        }
+        impl Event for Key
+        {
+            fn name(&self) -> String { format!("key {}", self.0) }
+        }
+
+        let (tx, mut rx) = channel();
+        register_casters!(rx, dyn Event => Click, Key);
+        tx.send(Click(1)).unwrap();
+        tx.send(7u8).unwrap();
+        tx.send(Key('a')).unwrap();
+        assert_eq!(rx.recv_dyn::<dyn Event>().unwrap().name(), "click 1");
+        assert_eq!(rx.recv_dyn::<dyn Event>().err(), Some(AnyRecvError::WrongType(TypeId::of::<u8>(), Box::new(7u8))));
+        assert_eq!(rx.recv_dyn::<dyn Event>().unwrap().name(), "key a");
+
+        #[cfg(feature = "buf_recv")]
+        {
+            let mut rx = rx.into_buffered();
+            tx.send(Key('b')).unwrap();
+            tx.send(8u8).unwrap();
+            assert_eq!(rx.recv::<u8>(), Err(AnyRecvError::BufRecvError(TypeId::of::<Key>())));
+            assert_eq!(rx.recv_dyn::<dyn Event>().unwrap().name(), "key b");
+            tx.send(Click(2)).unwrap();
+            assert_eq!(rx.recv_dyn::<dyn Event>().err(), Some(AnyRecvError::BufRecvError(TypeId::of::<u8>())));
+            assert_eq!(rx.recv_dyn::<dyn Event>().unwrap().name(), "click 2");
+            assert_eq!(rx.recv::<u8>().unwrap(), 8);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn recv_as_test()
+    {
+        trait Shape
+        {
+            fn sides(&self) -> u32;
+        }
+        struct Triangle;
+        struct Square;
+        impl Shape for Triangle
+        {
+            fn sides(&self) -> u32 { 3 }
+        }
+        impl Shape for Square
+        {
+            fn sides(&self) -> u32 { 4 }
+        }
+
+        let (tx, mut rx) = buffered_channel();
+        register_trait!(rx, Triangle => dyn Shape);
+        register_trait!(rx, Square => dyn Shape);
+        tx.send(Square).unwrap();
+        tx.send(1u8).unwrap();
+        tx.send(Triangle).unwrap();
+        assert_eq!(rx.recv_as::<dyn Shape>().unwrap().sides(), 4);
+        assert_eq!(rx.recv_as::<dyn Shape>().err(), Some(AnyRecvError::BufRecvError(TypeId::of::<u8>())));
+        assert_eq!(rx.recv_as::<dyn Shape>().unwrap().sides(), 3);
+        assert_eq!(rx.recv::<u8>(), Ok(1));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn recv_any_buf_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        assert!(rx.recv_any_buf().is_none());
+        tx.send(1u8).unwrap();
+        tx.send(2u8).unwrap();
+        tx.send(String::from("three")).unwrap();
+        assert_eq!(rx.recv_until::<String>().unwrap(), "three");
+
+        let (fwd_tx, fwd_rx) = channel();
+        while let Some(r) = rx.recv_any_buf()
+        {
+            fwd_tx.0.send(r).unwrap();
+        }
+        assert_eq!(fwd_rx.recv::<u8>().unwrap(), 1);
+        assert_eq!(fwd_rx.recv::<u8>().unwrap(), 2);
+
+        tx.send(4u32).unwrap();
+        rx.flush_channel_to_buffer();
+        tx.send('x').unwrap();
+        assert_eq!(*rx.try_recv_any().unwrap().downcast::<u32>().unwrap(), 4);
+        assert_eq!(*rx.try_recv_any().unwrap().downcast::<char>().unwrap(), 'x');
+        assert!(matches!(rx.try_recv_any(), Err(AnyRecvError::TryRecvError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn any_buffer_test()
+    {
+        #[derive(Debug, Default)]
+        struct VecBuffer(Vec<Box<dyn Any>>);
+
+        impl AnyBuffer for VecBuffer
+        {
+            fn insert_dyn(&mut self, t: Box<dyn Any>)
+            {
+                self.0.push(t);
+            }
+
+            fn find_type(&self, pred: &dyn Fn(TypeId) -> bool) -> Option<TypeId>
+            {
+                self.0.iter().map(|t| t.as_ref().type_id()).find(|type_id| pred(*type_id))
+            }
+
+            fn remove_where_dyn(&mut self, type_id: TypeId, pred: &dyn Fn(&dyn Any) -> bool) -> Option<Box<dyn Any>>
+            {
+                let index = self.0.iter().position(|t| t.as_ref().type_id() == type_id && pred(t.as_ref()))?;
+                Some(self.0.remove(index))
+            }
+
+            fn iter_dyn(&self, type_id: TypeId) -> Box<dyn Iterator<Item = &dyn Any> + '_>
+            {
+                Box::new(self.0.iter().filter(move |t| t.as_ref().type_id() == type_id).map(|t| t.as_ref()))
+            }
+
+            fn peek_dyn_mut(&mut self, type_id: TypeId) -> Option<&mut dyn Any>
+            {
+                self.0.iter_mut().find(|t| t.as_ref().type_id() == type_id).map(|t| t.as_mut())
+            }
+
+            fn len(&self) -> usize
+            {
+                self.0.len()
+            }
+
+            fn len_of(&self, type_id: TypeId) -> usize
+            {
+                self.0.iter().filter(|t| t.as_ref().type_id() == type_id).count()
+            }
+
+            fn clear(&mut self)
+            {
+                self.0.clear();
+            }
+        }
+
+        let (tx, rx) = channel();
+        let mut rx = BufferedReceiver::new(rx.0, VecBuffer::default());
+        tx.send(1u8).unwrap();
+        tx.send(String::from("two")).unwrap();
+        tx.send(3u8).unwrap();
+        tx.send(4u32).unwrap();
+        assert_eq!(rx.recv_until::<u32>().unwrap(), 4);
+        assert_eq!(rx.buffer_len(), 3);
+        assert!(rx.buffer_has::<String>());
+
+        // Insertion order is kept across types
+        assert_eq!(*rx.recv_any().unwrap().downcast::<u8>().unwrap(), 1);
+        assert_eq!(rx.ready(), Some(TypeId::of::<String>()));
+        assert_eq!(rx.recv::<u8>().unwrap(), 3);
+        assert_eq!(rx.drain_of::<String>().0, vec![String::from("two")]);
+    }
+
+    #[test]
+    pub fn tap_test()
+    {
+        let (tx, mut rx) = channel();
+        let (monitor_tx, monitor) = channel();
+        rx.tap(monitor_tx);
+        rx.register_tap_clone::<String>();
+
+        tx.send(String::from("first")).unwrap();
+        tx.send(2u8).unwrap();
+        tx.send(String::from("third")).unwrap();
+        assert_eq!(rx.recv::<String>().unwrap(), "first");
+        assert_eq!(rx.recv::<u8>().unwrap(), 2);
+        assert!(rx.ready_is::<String>());
+        assert_eq!(monitor.try_recv::<String>().unwrap(), "first");
+        assert!(matches!(monitor.try_recv::<String>(), Err(AnyRecvError::TryRecvError(_))));
+        assert_eq!(rx.recv_any().unwrap().downcast_ref::<String>().unwrap(), "third");
+        assert_eq!(monitor.try_recv::<String>().unwrap(), "third");
+
+        #[cfg(feature = "buf_recv")]
+        {
+            let mut rx = rx.into_buffered();
+            tx.send(String::from("buffered")).unwrap();
+            tx.send(5u8).unwrap();
+            assert_eq!(rx.recv_until::<u8>().unwrap(), 5);
+            assert!(matches!(monitor.try_recv::<String>(), Err(AnyRecvError::TryRecvError(_))));
+            assert_eq!(rx.recv::<String>().unwrap(), "buffered");
+            assert_eq!(monitor.try_recv::<String>().unwrap(), "buffered");
+        }
+    }
+
+    #[test]
+    pub fn recover_as_test()
+    {
+        let (tx, rx) = channel();
+        tx.send(String::from("mistyped")).unwrap();
+        let err = rx.recv::<i32>().unwrap_err();
+        assert_eq!(err.recover_as::<String>().unwrap(), "mistyped");
+        assert!(err.recover_as::<i32>().is_none());
+
+        let err = err.into_recovered::<i32>().unwrap_err();
+        assert_eq!(err.into_recovered::<String>().unwrap(), "mistyped");
+
+        drop(tx);
+        let err = rx.recv::<i32>().unwrap_err();
+        assert!(err.recover_as::<String>().is_none());
+        assert!(matches!(err.into_recovered::<String>(), Err(AnyRecvError::RecvError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn ordered_buffer_test()
+    {
+        let (tx, rx) = channel();
+        let mut rx = BufferedReceiver::new(rx.0, OrderedBuffer::new());
+        tx.send(1u32).unwrap();
+        tx.send(String::from("two")).unwrap();
+        tx.send(3u32).unwrap();
+        tx.send('x').unwrap();
+        assert_eq!(rx.recv_until::<char>().unwrap(), 'x');
+        assert_eq!(rx.ready(), Some(TypeId::of::<u32>()));
+
+        let values = rx.buf.drain();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0].downcast_ref::<u32>(), Some(&1));
+        assert_eq!(values[1].downcast_ref::<String>().unwrap(), "two");
+        assert_eq!(values[2].downcast_ref::<u32>(), Some(&3));
+        assert!(rx.buf.is_empty());
+    }
+
+    #[test]
+    pub fn forward_test()
+    {
+        let (tx, rx) = channel();
+        let (legacy_tx, legacy_rx) = mpsc::channel::<u32>();
+        let (other_tx, other_rx) = mpsc::channel::<String>();
+        let numbers = forward(legacy_rx, tx.clone());
+        let strings = forward(other_rx, tx.clone());
+
+        for i in 0..100
+        {
+            legacy_tx.send(i).unwrap();
+            other_tx.send(i.to_string()).unwrap();
+        }
+        drop((legacy_tx, other_tx));
+        assert_eq!(numbers.join().unwrap(), 100);
+        assert_eq!(strings.join().unwrap(), 100);
+
+        drop(tx);
+        let (numbers, rest) = rx.drain_of::<u32>();
+        assert_eq!(numbers, (0..100).collect::<Vec<_>>());
+        assert_eq!(partition::<String>(rest).0, (0..100).map(|i| i.to_string()).collect::<Vec<_>>());
+
+        let (tx, rx) = channel();
+        let (_legacy_tx, legacy_rx) = mpsc::channel::<u8>();
+        let handle = forward(legacy_rx, tx);
+        handle.stop();
+        assert_eq!(handle.join().unwrap(), 0);
+        assert!(rx.is_disconnected());
+    }
+
+    #[test]
+    pub fn timestamped_test()
+    {
+        use std::{thread, time::{Duration, Instant}};
+
+        let (tx, rx) = channel();
+        let before = Instant::now();
+        tx.send_timestamped(String::from("latency")).unwrap();
+        tx.send(5u8).unwrap();
+        thread::sleep(Duration::from_millis(5));
+
+        let msg = rx.recv_timestamped::<String>().unwrap();
+        assert_eq!(msg.len(), 7);
+        assert_eq!(msg.value, "latency");
+        assert!(msg.sent_at >= before);
+        assert!(msg.sent_at.elapsed() >= Duration::from_millis(5));
+        assert!(matches!(rx.recv_timestamped::<u8>(), Err(AnyRecvError::WrongType(..))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn recv_where_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        let allowed = [TypeId::of::<String>()];
+        tx.send(1u8).unwrap();
+        tx.send(2u32).unwrap();
+        tx.send(String::from("three")).unwrap();
+        tx.send(String::from("four")).unwrap();
+
+        let r = rx.recv_where(|r| allowed.contains(&r.type_id())).unwrap();
+        assert_eq!(r.downcast_ref::<String>().unwrap(), "three");
+        assert_eq!(rx.buffer_len(), 2);
+        assert!(rx.buffer_has::<u8>() && rx.buffer_has::<u32>());
+
+        let r = rx.recv_where(|r| r.downcast_ref::<u32>() == Some(&2)).unwrap();
+        assert_eq!(r.downcast_ref::<u32>(), Some(&2));
+        assert_eq!(rx.recv::<String>().unwrap(), "four");
+        assert_eq!(rx.recv_buf::<u8>().unwrap(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn live_recv_skips_buffer_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        rx.buf.insert(1u8);
+        assert!(matches!(rx.try_recv_live::<u8>(), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty))));
+        assert!(matches!(rx.recv_timeout_live::<u8>(std::time::Duration::from_millis(10)), Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout))));
+
+        tx.send(2u8).unwrap();
+        tx.send(3u8).unwrap();
+        tx.send(4u8).unwrap();
+        assert_eq!(rx.try_recv_live::<u8>().unwrap(), 2);
+        assert_eq!(rx.recv_timeout_live::<u8>(std::time::Duration::from_millis(10)).unwrap(), 3);
+        assert_eq!(rx.recv_live::<u8>().unwrap(), 4);
+        assert_eq!(rx.recv_buf::<u8>().unwrap(), 1);
+    }
+
+    #[cfg(any(feature = "crossbeam", feature = "flume"))]
+    #[test]
+    pub fn same_channel_test()
+    {
+        let (tx, _rx) = channel();
+        let (other_tx, _other_rx) = channel();
+        assert!(tx.same_channel(&tx.clone()));
+        assert!(tx.clone().same_channel(&tx));
+        assert!(!tx.same_channel(&other_tx));
+        assert!(AnySender(tx.0.clone()).same_channel(&tx));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn global_ordered_test()
+    {
+        let (tx, rx) = channel();
+        let mut rx = rx.into_global_ordered();
+        tx.send(String::from("first")).unwrap();
+        tx.send(1i32).unwrap();
+        tx.send(String::from("second")).unwrap();
+        tx.send(2i32).unwrap();
+        tx.send('x').unwrap();
+
+        assert_eq!(rx.recv::<i32>().unwrap(), 1);
+        assert_eq!(rx.len(), 1);
+        assert_eq!(rx.recv::<char>().unwrap(), 'x');
+        assert_eq!(rx.len(), 3);
+        assert_eq!(rx.recv_any().unwrap().downcast_ref::<String>().unwrap(), "first");
+        assert_eq!(rx.recv_any().unwrap().downcast_ref::<String>().unwrap(), "second");
+        assert_eq!(rx.try_recv::<i32>().unwrap(), 2);
+        assert!(rx.is_empty());
+        assert!(matches!(rx.try_recv::<i32>(), Err(AnyRecvError::TryRecvError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn recv_until_timeout_test()
+    {
+        use std::{thread, time::{Duration, Instant}};
+
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1u8).unwrap();
+        tx.send(String::from("wanted")).unwrap();
+        assert_eq!(rx.recv_until_timeout::<String>(Duration::from_millis(50)).unwrap(), "wanted");
+        assert_eq!(rx.buffer_len(), 1);
+
+        let noisy_tx = tx.clone();
+        let noise = thread::spawn(move ||
+        {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_millis(200)
+            {
+                noisy_tx.send(0u32).unwrap();
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+        let start = Instant::now();
+        assert!(matches!(rx.recv_until_timeout::<String>(Duration::from_millis(30)), Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout))));
+        assert!(start.elapsed() < Duration::from_millis(150));
+        noise.join().unwrap();
+        assert!(rx.buffer_has::<u32>());
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let sender = thread::spawn(move ||
+        {
+            thread::sleep(Duration::from_millis(10));
+            tx.send(2u8).unwrap();
+            drop(tx);
+        });
+        let start = Instant::now();
+        assert!(matches!(rx.recv_until_deadline::<String>(deadline), Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Disconnected))));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        sender.join().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn recv_repeated_test()
+    {
+        use std::time::{Duration, Instant};
+
+        struct Ack(u32);
+        let (tx, mut rx) = buffered_channel();
+        for i in 0..3
+        {
+            tx.send(Ack(i)).unwrap();
+            tx.send(i as u8).unwrap();
+        }
+        let acks = rx.recv_repeated::<Ack>(3).ok().unwrap();
+        assert_eq!(acks.iter().map(|ack| ack.0).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(rx.recv_repeated_timeout::<u8>(3, Duration::from_millis(10)).unwrap(), vec![0, 1, 2]);
+
+        tx.send(7u8).unwrap();
+        match rx.recv_repeated_deadline::<u8>(2, Instant::now() + Duration::from_millis(10))
+        {
+            Err((values, AnyRecvError::RecvTimeoutError(_))) => assert_eq!(values, vec![7]),
+            result => panic!("expected a timeout, got {:?}", result.map(|_| ()))
+        }
+    }
+
+    #[test]
+    pub fn recv_or_shutdown_test()
+    {
+        let (tx, rx) = channel();
+        tx.send(1u8).unwrap();
+        tx.send(String::from("other")).unwrap();
+        tx.send(2u8).unwrap();
+        drop(tx);
+
+        let mut received = Vec::new();
+        let mut wrong_types = 0;
+        while let ControlFlow::Continue(result) = rx.recv_or_shutdown::<u8>()
+        {
+            match result
+            {
+                Ok(t) => received.push(t),
+                Err(_) => wrong_types += 1
+            }
+        }
+        assert_eq!((received, wrong_types), (vec![1, 2], 1));
+        assert_eq!(rx.recv_or_shutdown::<u8>(), ControlFlow::Break(()));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffered_type_names_test()
+    {
+        struct Unnamed;
+        register_type_name::<String>();
+        let (tx, mut rx) = buffered_channel();
+        tx.send(String::from("spilled")).unwrap();
+        tx.send(String::from("again")).unwrap();
+        tx.0.send(Box::new(Unnamed)).unwrap();
+        tx.send(1u32).unwrap();
+        assert_eq!(rx.recv_until::<u32>(), Ok(1));
+        let mut names = rx.buffered_type_names();
+        names.sort();
+        assert_eq!(names, vec!["<unknown>", std::any::type_name::<String>(), std::any::type_name::<String>()]);
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn buffer_introspection_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1u8).unwrap();
+        tx.send(2u8).unwrap();
+        tx.send(String::from("three")).unwrap();
+        tx.send('x').unwrap();
+        assert_eq!(rx.recv_until::<char>().unwrap(), 'x');
+
+        assert_eq!(rx.buffer_len(), 3);
+        assert_eq!(rx.buffer_len_of::<u8>(), 2);
+        assert_eq!(rx.buffer_len_of::<String>(), 1);
+        assert_eq!(rx.buffer_len_of::<char>(), 0);
+        assert!(rx.buffer_has::<String>());
+
+        let mut types = rx.buffer_types();
+        types.sort();
+        let mut expected = vec![TypeId::of::<u8>(), TypeId::of::<String>()];
+        expected.sort();
+        assert_eq!(types, expected);
+        register_type_name::<u8>();
+        register_type_name::<String>();
+        let mut names = rx.buffer_type_names();
+        names.sort();
+        assert_eq!(names, vec![Some("alloc::string::String"), Some("u8")]);
+
+        let (tx, rx) = channel();
+        let mut rx = BufferedReceiver::new(rx.0, OrderedBuffer::new());
+        tx.send(1u8).unwrap();
+        tx.send(String::from("two")).unwrap();
+        tx.send(3u8).unwrap();
+        tx.send('x').unwrap();
+        assert_eq!(rx.recv_until::<char>().unwrap(), 'x');
+        assert_eq!(rx.buffer_types(), vec![TypeId::of::<u8>(), TypeId::of::<String>()]);
+        assert_eq!(rx.buffer_len_of::<u8>(), 2);
+
+        struct BoxedOnly;
+        let (tx, mut rx) = buffered_channel();
+        tx.send_boxed(Box::new(BoxedOnly)).unwrap();
+        tx.send(String::from("spilled")).unwrap();
+        tx.send('x').unwrap();
+        assert_eq!(rx.recv_until::<char>().unwrap(), 'x');
+        let mut names = rx.buffer_type_names();
+        names.sort();
+        assert_eq!(names, vec![None, Some("alloc::string::String")]);
+    }
+
+    #[test]
+    pub fn arc_channel_test()
+    {
+        use std::sync::Arc;
+
+        let (tx, rx) = arc_channel();
+        let big = Arc::new(vec![0u8; 1024]);
+        tx.send(big.clone()).unwrap();
+        let received = rx.recv::<Vec<u8>>().unwrap();
+        assert!(Arc::ptr_eq(&big, &received));
+        assert_eq!(Arc::strong_count(&big), 2);
+
+        register_type_name::<String>();
+        tx.send(Arc::new(String::from("shared"))).unwrap();
+        let err = rx.try_recv::<u32>().unwrap_err();
+        assert_eq!(err, AnyRecvError::WrongType(TypeId::of::<String>(), Box::new(())));
+        assert_eq!(err.received_type_name(), Some("alloc::string::String"));
+        let r = err.into_recovered::<Arc<dyn Any + Send + Sync>>().unwrap();
+        assert_eq!(r.downcast_ref::<String>().unwrap(), "shared");
+
+        drop(tx);
+        assert!(matches!(rx.recv::<u32>(), Err(AnyRecvError::RecvError(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "buf_recv")]
+    pub fn drain_buf_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        for i in 0..5u32
+        {
+            tx.send(i).unwrap();
+            tx.send(i as u8).unwrap();
+        }
+        tx.send('x').unwrap();
+        assert_eq!(rx.recv_until::<char>().unwrap(), 'x');
+        assert_eq!(rx.drain_buf::<u32>(), vec![0, 1, 2, 3, 4]);
+        assert!(rx.drain_buf::<u32>().is_empty());
+
+        tx.send(5u32).unwrap();
+        let all = rx.drain_buf_all();
+        assert_eq!(all.len(), 5);
+        assert!(all.iter().all(|r| r.is::<u8>()));
+        assert_eq!(rx.buffer_len(), 0);
+
+        tx.send(String::from("a")).unwrap();
+        rx.flush_channel_to_buffer();
+        tx.send(6u32).unwrap();
+        tx.send(String::from("b")).unwrap();
+        assert_eq!(rx.recv_all::<u32>(), vec![5, 6]);
+        assert_eq!(rx.recv_all::<String>(), vec!["a", "b"]);
+        assert!(rx.recv_all::<u32>().is_empty());
+    }
+
+    #[test]
+    pub fn context_test()
+    {
+        fn next_count(rx: &AnyReceiver) -> Result<u32, AnyRecvContext>
+        {
+            let count = rx.try_recv::<u32>().context("reading the count")?;
+            Ok(count + 1)
+        }
+
+        let (tx, rx) = channel();
+        tx.send(1u32).unwrap();
+        assert_eq!(next_count(&rx), Ok(2));
+
+        register_type_name::<String>();
+        tx.send(String::from("oops")).unwrap();
+        let err = next_count(&rx).unwrap_err();
+        assert_eq!(err.to_string(), "reading the count");
+        assert_eq!(err.error, AnyRecvError::WrongType(TypeId::of::<String>(), Box::new(())));
+        assert!(err.source().unwrap().to_string().contains("alloc::string::String"));
+
+        let err = rx.try_recv::<u32>().with_context(|| format!("reading count {}", 2)).unwrap_err();
+        assert_eq!(err.message, "reading count 2");
+        assert!(matches!(err.error, AnyRecvError::TryRecvError(_)));
+
+        #[cfg(feature = "anyhow")]
+        {
+            let err = AnyRecvError::Cancelled.context("waiting for work").into_anyhow();
+            assert_eq!(err.to_string(), "waiting for work");
+            assert_eq!(err.root_cause().downcast_ref::<std::io::Error>().unwrap().kind(), std::io::ErrorKind::Interrupted);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn ser_channel_test()
+    {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Point
+        {
+            x: i32,
+            y: i32,
+            label: String
+        }
+
+        let mut registry = SerRegistry::new();
+        registry.register::<Point>("point").register::<u64>("u64");
+        assert_eq!(registry.tag_of::<Point>(), Some("point"));
+        let (tx, rx) = ser_channel(registry);
+
+        tx.send(Point { x: 3, y: -4, label: String::from("p") }).unwrap();
+        tx.send(7u64).unwrap();
+        assert!(matches!(tx.send(1u8), Err(SerSendError::Unregistered("u8"))));
+        assert_eq!(rx.recv::<Point>().unwrap(), Point { x: 3, y: -4, label: String::from("p") });
+        assert!(matches!(rx.try_recv::<Point>(), Err(SerRecvError::RecvError(AnyRecvError::WrongType(..)))));
+
+        tx.0.send((String::from("nope"), vec![])).unwrap();
+        tx.0.send((String::from("point"), vec![1])).unwrap();
+        assert!(matches!(rx.recv_any(), Err(SerRecvError::UnknownTag(tag)) if tag == "nope"));
+        assert!(matches!(rx.recv_any(), Err(SerRecvError::Decode(tag, _)) if tag == "point"));
+
+        // The bytes may travel over any transport to another registry
+        let (tag, bytes) = (String::from("u64"), bincode::serialize(&9u64).unwrap());
+        let (other_tx, other_rx) = mpsc::channel();
+        let mut registry = SerRegistry::new();
+        registry.register::<u64>("u64");
+        let other_rx = SerReceiver::new(other_rx, std::sync::Arc::new(registry));
+        other_tx.send((tag, bytes)).unwrap();
+        assert_eq!(other_rx.recv::<u64>().unwrap(), 9);
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn clear_buf_test()
+    {
+        use std::rc::Rc;
+
+        let dropped = Rc::new(());
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1u8).unwrap();
+        tx.send(2u8).unwrap();
+        tx.send(3u16).unwrap();
+        tx.send(String::from("keep")).unwrap();
+        tx.send(String::from("purge")).unwrap();
+        rx.flush_channel_to_buffer();
+        tx.send(4u8).unwrap();
+
+        assert_eq!(rx.clear_buf_of::<u8>(), 2);
+        assert_eq!(rx.clear_buf_of::<u8>(), 0);
+        assert_eq!(rx.recv::<u8>(), Ok(4));
+
+        rx.retain_buf(|type_id, t| type_id != TypeId::of::<String>() || t.downcast_ref::<String>().unwrap() == "keep");
+        assert_eq!(rx.buffer_len(), 2);
+        tx.send(String::from("new")).unwrap();
+        assert_eq!(rx.recv::<String>().unwrap(), "keep");
+        assert_eq!(rx.recv::<String>().unwrap(), "new");
+
+        rx.buf.insert(dropped.clone());
+        assert_eq!(Rc::strong_count(&dropped), 2);
+        rx.clear_buf();
+        assert_eq!(Rc::strong_count(&dropped), 1);
+        assert!(rx.buf.is_empty());
+        tx.send(5u16).unwrap();
+        assert_eq!(rx.recv::<u16>(), Ok(5));
+
+        let mut rx = BufferedReceiver::new(rx.rx, OrderedBuffer::new());
+        tx.send(1u8).unwrap();
+        tx.send(2u16).unwrap();
+        tx.send(3u8).unwrap();
+        rx.flush_channel_to_buffer();
+        rx.retain_buf(|_, t| t.downcast_ref::<u8>() != Some(&1));
+        assert_eq!(rx.clear_buf_of::<u16>(), 1);
+        assert_eq!(rx.buffer_len(), 1);
+        assert_eq!(rx.recv::<u8>(), Ok(3));
+    }
+
+    #[test]
+    pub fn merge_receivers_test()
+    {
+        let (tx_a, rx_a) = channel();
+        let (tx_b, rx_b) = channel();
+        let rx = merge_receivers(vec![rx_a, rx_b]);
+        let handle = std::thread::spawn(move ||
+        {
+            for i in 0..3u32
+            {
+                tx_b.send(i).unwrap();
+            }
+        });
+        tx_a.send(String::from("a")).unwrap();
+        handle.join().unwrap();
+        drop(tx_a);
+
+        let mut strings = Vec::new();
+        let mut numbers = Vec::new();
+        while let Ok(r) = rx.recv_any()
+        {
+            match r.downcast::<u32>()
+            {
+                Ok(n) => numbers.push(*n),
+                Err(r) => strings.push(*r.downcast::<String>().unwrap())
+            }
+        }
+        assert_eq!(numbers, vec![0, 1, 2]);
+        assert_eq!(strings, vec![String::from("a")]);
+        assert!(rx.is_disconnected());
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn merge_buffered_receivers_test()
+    {
+        let (tx_a, rx_a) = channel();
+        let (tx_b, rx_b) = channel();
+        let mut rx = merge_buffered_receivers(vec![rx_a, rx_b]);
+        tx_a.send(1u8).unwrap();
+        tx_b.send(2u16).unwrap();
+        assert_eq!(rx.recv_until::<u16>(), Ok(2));
+        assert_eq!(rx.recv_until::<u8>(), Ok(1));
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn buffer_limit_test()
+    {
+        use std::time::Duration;
+
+        let (tx, mut rx) = buffered_channel_with(2, OverflowPolicy::DropNewest);
+        for i in 0..4u8
+        {
+            tx.send(i).unwrap();
+        }
+        assert_eq!(rx.try_recv::<i32>(), Err(AnyRecvError::BufRecvError(TypeId::of::<u8>())));
+        assert_eq!(rx.recv_timeout::<i32>(Duration::from_millis(1)), Err(AnyRecvError::BufRecvError(TypeId::of::<u8>())));
+        assert_eq!(rx.recv::<i32>(), Err(AnyRecvError::BufRecvError(TypeId::of::<u8>())));
+        assert_eq!(rx.recv_live::<i32>(), Err(AnyRecvError::BufRecvError(TypeId::of::<u8>())));
+        assert_eq!(rx.buffer_len(), 2);
+        assert_eq!(rx.dropped_count(), 2);
+        assert_eq!(rx.dropped_counts()[&TypeId::of::<u8>()], 2);
+        assert_eq!(rx.drain_buf::<u8>(), vec![0, 1]);
+
+        rx.set_buffer_limit(BufferLimit::total(2, OverflowPolicy::DropOldest));
+        tx.send(1u8).unwrap();
+        tx.send(2u16).unwrap();
+        tx.send(3u8).unwrap();
+        tx.send(4u8).unwrap();
+        tx.send(5u32).unwrap();
+        rx.flush_channel_to_buffer();
+        assert_eq!(rx.buffer_len(), 2);
+        assert_eq!(rx.buffer_len_of::<u32>(), 1);
+        // The only u8 left, if any, is the newest
+        assert_eq!(rx.buffer_len_of::<u8>() + rx.buffer_len_of::<u16>(), 1);
+        assert!(matches!(rx.recv_buf::<u8>(), Ok(4) | Err(AnyRecvError::EmptyBuffer)));
+        assert_eq!(rx.dropped_count(), 5);
+
+        let (tx, mut rx) = buffered_channel_with(3, OverflowPolicy::DropOldest);
+        for i in 0..5u8
+        {
+            tx.send(i).unwrap();
+        }
+        rx.flush_channel_to_buffer();
+        assert_eq!(rx.buffer_len(), 3);
+        assert_eq!(rx.dropped_counts()[&TypeId::of::<u8>()], 2);
+        assert_eq!(rx.drain_buf::<u8>(), vec![2, 3, 4]);
+
+        let (tx, mut rx) = buffered_channel();
+        rx.set_buffer_limit(BufferLimit::per_type(1, OverflowPolicy::Error));
+        tx.send(1u8).unwrap();
+        tx.send(2u8).unwrap();
+        tx.send(3u16).unwrap();
+        tx.send(4i32).unwrap();
+        assert_eq!(rx.recv_until::<i32>(), Err(AnyRecvError::BufferFull(TypeId::of::<u8>())));
+        assert_eq!(rx.recv_until::<i32>(), Ok(4));
+        assert_eq!(rx.recv_buf::<u8>(), Ok(1));
+        assert_eq!(rx.recv_buf::<u16>(), Ok(3));
+        assert_eq!(rx.dropped_count(), 1);
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn buffer_limit_keeps_requested_values_test()
+    {
+        for policy in [OverflowPolicy::DropNewest, OverflowPolicy::Error]
+        {
+            // Nothing fits, so only values of the type asked for are kept
+            let (tx, mut rx) = buffered_channel_with(0, policy);
+            tx.send(1u8).unwrap();
+            assert_eq!(rx.ready(), Some(TypeId::of::<u8>()));
+            assert_eq!(rx.recv::<u8>(), Ok(1));
+
+            tx.send(2u8).unwrap();
+            assert!(rx.ready_is::<u8>());
+            assert_eq!(rx.recv::<u8>(), Ok(2));
+            tx.send(3u8).unwrap();
+            assert!(!rx.ready_is::<u16>());
+            assert_eq!(rx.recv::<u8>(), Ok(3));
+
+            tx.send('x').unwrap();
+            tx.send(4u8).unwrap();
+            assert_eq!(rx.recv_inspect(|t: &u8| *t * 2), Ok(8));
+            assert_eq!(rx.recv::<u8>(), Ok(4));
+
+            tx.send(5u8).unwrap();
+            tx.send(50u8).unwrap();
+            assert_eq!(rx.recv_while(|t: &u8| *t < 10), vec![5]);
+            assert_eq!(rx.recv::<u8>(), Ok(50));
+
+            tx.send(6u8).unwrap();
+            tx.send(7u8).unwrap();
+            assert_eq!(rx.filter_recv(|t: &u8| *t == 7), Ok(7));
+            assert_eq!(rx.recv::<u8>(), Ok(6));
+
+            assert_eq!(rx.dropped_count(), 1);
+            assert_eq!(rx.buffer_len(), 0);
+        }
+    }
+
+    #[test]
+    pub fn fanout_sender_test()
+    {
+        let (tx_a, rx_a) = channel();
+        let (tx_b, rx_b) = channel();
+        let tx = fanout_sender(vec![tx_a, tx_b]);
+        tx.send(String::from("hello")).unwrap();
+        assert_eq!(rx_a.recv::<String>().unwrap(), "hello");
+        assert_eq!(rx_b.recv::<String>().unwrap(), "hello");
+
+        drop(rx_a);
+        let results = tx.send(5u8).unwrap_err();
+        assert!(results[0].is_err());
+        assert!(results[1].is_ok());
+        assert_eq!(rx_b.recv::<u8>(), Ok(5));
+        #[cfg(feature = "flume")]
+        {
+            assert!(!tx.is_disconnected());
+            drop(rx_b);
+            assert!(tx.is_disconnected());
+        }
+    }
+
+    #[test]
+    pub fn recv_deadline_test()
+    {
+        use std::time::{Duration, Instant};
+        let (tx, rx) = channel();
+        let start = Instant::now();
+        assert_eq!(
+            rx.recv_deadline::<u8>(start + Duration::from_millis(50)),
+            Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout)));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        tx.send(1u8).unwrap();
+        assert_eq!(rx.recv_deadline::<u8>(start), Ok(1));
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn buffered_recv_deadline_test()
+    {
+        use std::time::{Duration, Instant};
+        let (tx, mut rx) = buffered_channel();
+        let deadline = Instant::now() + Duration::from_millis(50);
+        assert_eq!(
+            rx.recv_deadline::<u8>(deadline),
+            Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout)));
+        assert!(Instant::now() >= deadline);
+        tx.send(1u8).unwrap();
+        tx.send(2u16).unwrap();
+        assert_eq!(rx.recv_deadline::<u16>(deadline), Err(AnyRecvError::BufRecvError(TypeId::of::<u8>())));
+        assert_eq!(rx.recv_deadline_live::<u16>(deadline), Ok(2));
+        assert_eq!(rx.recv_deadline::<u8>(deadline), Ok(1));
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn global_ordered_dispatch_test()
+    {
+        use std::{cell::RefCell, rc::Rc};
+
+        let (tx, mut rx) = global_ordered_channel();
+        for i in 0..3u8
+        {
+            tx.send(i).unwrap();
+            tx.send(format!("s{}", i)).unwrap();
+            tx.send(i as f32).unwrap();
+        }
+        // Pull one type out first, so the rest are replayed from the queue
+        assert_eq!(rx.recv::<f32>(), Ok(0.0));
+        drop(tx);
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut dispatcher = Dispatcher::new();
+        let l = log.clone();
+        dispatcher.register(move |i: u8| l.borrow_mut().push(format!("u8 {}", i)));
+        let l = log.clone();
+        dispatcher.register(move |s: String| l.borrow_mut().push(format!("String {}", s)));
+        let l = log.clone();
+        dispatcher.register(move |f: f32| l.borrow_mut().push(format!("f32 {}", f)));
+        rx.dispatch(&mut dispatcher).unwrap();
+        assert_eq!(*log.borrow(), vec![
+            "u8 0", "String s0",
+            "u8 1", "String s1", "f32 1",
+            "u8 2", "String s2", "f32 2"]);
+    }
+
+    #[test]
+    pub fn poisonable_channel_test()
+    {
+        let (tx, rx) = poisonable_channel();
+        let clean_tx = tx.clone();
+        std::thread::spawn(move || clean_tx.send(1u8).unwrap()).join().unwrap();
+        assert!(!rx.is_poisoned());
+        assert_eq!(rx.recv::<u8>(), Ok(1));
+
+        let result = std::thread::spawn(move ||
+        {
+            tx.send(2u8).unwrap();
+            panic!("worker failed");
+        }).join();
+        assert!(result.is_err());
+        assert!(rx.is_poisoned());
+        assert_eq!(rx.try_recv::<u8>(), Err(AnyRecvError::Poisoned));
+        assert_eq!(rx.recv::<u8>(), Err(AnyRecvError::Poisoned));
+
+        rx.clear_poison();
+        assert_eq!(rx.recv::<u8>(), Ok(2));
+        assert!(matches!(rx.recv::<u8>(), Err(AnyRecvError::RecvError(_))));
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn recv_until_with_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1u8).unwrap();
+        tx.send(2u16).unwrap();
+        tx.send(3u8).unwrap();
+        tx.send(String::from("done")).unwrap();
+        tx.send(4u8).unwrap();
+
+        let mut spilled = Vec::new();
+        let result = rx.recv_until_with::<String>(|type_id, r| spilled.push((type_id, r)));
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(spilled.len(), 3);
+        assert_eq!(spilled[1].0, TypeId::of::<u16>());
+        assert_eq!(spilled[2].1.downcast_ref::<u8>(), Some(&3));
+        assert!(rx.buf.is_empty());
+        assert_eq!(rx.recv::<u8>(), Ok(4));
+
+        drop(tx);
+        assert!(matches!(rx.recv_until_with::<String>(|_, _| ()), Err(AnyRecvError::RecvError(_))));
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn try_recv_until_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        for i in 0..100_000u32
+        {
+            tx.send(i).unwrap();
+        }
+        assert_eq!(rx.try_recv_until::<String>(), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty)));
+        assert_eq!(rx.buffer_len(), 100_000);
+
+        tx.send(1u8).unwrap();
+        tx.send(String::from("found")).unwrap();
+        tx.send(2u8).unwrap();
+        assert_eq!(rx.try_recv_until::<String>().unwrap(), "found");
+        assert_eq!(rx.buffer_len_of::<u8>(), 1);
+        assert_eq!(rx.try_recv_until::<u8>(), Ok(1));
+        assert_eq!(rx.try_recv_until::<u8>(), Ok(2));
+
+        drop(tx);
+        assert_eq!(rx.try_recv_until::<String>(), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Disconnected)));
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn recv_latest_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        for i in 1..=5i32
+        {
+            tx.send(i).unwrap();
+        }
+        tx.send(String::from("later")).unwrap();
+        assert_eq!(rx.recv_until::<String>().unwrap(), "later");
+        assert_eq!(rx.buffer_len(), 5);
+        assert_eq!(rx.recv_latest::<i32>(), Ok(5));
+        assert!(rx.buf.is_empty());
+
+        tx.send(6i32).unwrap();
+        tx.send(7i32).unwrap();
+        assert_eq!(rx.recv_latest::<i32>(), Ok(6));
+    }
+
+    #[test]
+    pub fn recv_boxed_timeout_test()
+    {
+        use std::time::Duration;
+        let (tx, rx) = channel();
+        tx.send(String::from("routed")).unwrap();
+        let (type_id, r) = rx.recv_boxed_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(type_id, TypeId::of::<String>());
+        assert_eq!(r.downcast_ref::<String>().unwrap(), "routed");
+        assert!(matches!(
+            rx.recv_boxed_timeout(Duration::from_millis(1)),
+            Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout))));
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn buffer_iter_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        for i in 0..3u8
+        {
+            tx.send(i).unwrap();
+            tx.send(format!("s{}", i)).unwrap();
+        }
+        rx.flush_channel_to_buffer();
+
+        let iter = rx.buf_iter::<String>();
+        assert_eq!(iter.len(), 3);
+        assert!(format!("{:?}", iter).contains("remaining: 3"));
+        assert_eq!(iter.map(String::as_str).collect::<Vec<_>>(), vec!["s0", "s1", "s2"]);
+        assert_eq!(rx.buffer_len(), 6);
+
+        let mut drain = rx.buf_drain_iter::<u8>();
+        assert_eq!(drain.size_hint(), (3, Some(3)));
+        assert_eq!(drain.next(), Some(0));
+        assert_eq!(drain.len(), 2);
+        assert_eq!(drain.collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(rx.buffer_len(), 3);
+
+        let worker = std::thread::spawn(move ||
+        {
+            tx.send(1.5f32).unwrap();
+            tx.send(String::from("s3")).unwrap();
+        });
+        let iter = rx.iter_of::<String>();
+        assert_eq!(iter.size_hint(), (3, None));
+        assert_eq!(iter.collect::<Vec<_>>(), vec!["s0", "s1", "s2", "s3"]);
+        worker.join().unwrap();
+        assert_eq!(rx.buf_iter::<f32>().copied().collect::<Vec<_>>(), vec![1.5]);
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn flush_to_channel_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1u8).unwrap();
+        tx.send(String::from("a")).unwrap();
+        tx.send(2u8).unwrap();
+        tx.send(1.5f32).unwrap();
+        rx.flush_channel_to_buffer();
+
+        let (target_tx, mut target_rx) = buffered_channel();
+        assert!(matches!(rx.flush_type_to_channel::<u8>(&target_tx), Ok(2)));
+        assert_eq!(rx.buffer_len(), 2);
+        assert_eq!(target_rx.try_recv_nobuf::<u8>(), Ok(1));
+        assert_eq!(target_rx.try_recv_nobuf::<u8>(), Ok(2));
+        assert!(target_rx.try_recv_any().is_err());
+
+        assert!(matches!(rx.flush_to_channel(&target_tx), Ok(2)));
+        assert_eq!(rx.buffer_len(), 0);
+        target_rx.flush_channel_to_buffer();
+        assert_eq!(target_rx.buffer_len(), 2);
+        assert_eq!(target_rx.recv_buf::<String>(), Ok(String::from("a")));
+        assert_eq!(target_rx.recv_buf::<f32>(), Ok(1.5));
+
+        tx.send(3u8).unwrap();
+        tx.send(4u8).unwrap();
+        tx.send(String::from("b")).unwrap();
+        rx.flush_channel_to_buffer();
+        drop(target_rx);
+        let (sent, e) = rx.flush_type_to_channel::<u8>(&target_tx).unwrap_err();
+        assert_eq!(sent, 0);
+        assert_eq!(e.0.downcast_ref::<u8>(), Some(&3));
+        assert_eq!(rx.buffer_len(), 2);
+        assert!(rx.flush_to_channel(&target_tx).is_err());
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn forward_buffered_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        for i in 1..=3u32
+        {
+            tx.send(i).unwrap();
+        }
+        tx.send('x').unwrap();
+        rx.flush_channel_to_buffer();
+
+        let (other_tx, other_rx) = channel();
+        assert_eq!(rx.forward_buffered::<u32>(&other_tx).unwrap(), 3);
+        assert_eq!(rx.buffer_len(), 1);
+        for i in 1..=3u32
+        {
+            assert_eq!(other_rx.try_recv::<u32>(), Ok(i));
+        }
+        assert!(other_rx.try_recv_any().is_err());
+
+        tx.send(4u32).unwrap();
+        rx.flush_channel_to_buffer();
+        drop(other_rx);
+        let e = rx.forward_buffered::<u32>(&other_tx).unwrap_err();
+        assert_eq!(e.0.downcast_ref::<u32>(), Some(&4));
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn peek_buf_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        assert_eq!(rx.peek_buf::<String>(), None);
+        tx.send(String::from("a")).unwrap();
+        tx.send(1u8).unwrap();
+        tx.send(String::from("b")).unwrap();
+        rx.flush_channel_to_buffer();
+
+        assert_eq!(rx.peek_buf::<String>().map(String::as_str), Some("a"));
+        assert_eq!(rx.buffer_len(), 3);
+        rx.peek_buf_mut::<String>().unwrap().push('!');
+        *rx.peek_buf_mut::<u8>().unwrap() += 1;
+        assert_eq!(rx.recv_buf::<String>(), Ok(String::from("a!")));
+        assert_eq!(rx.peek_buf::<String>().map(String::as_str), Some("b"));
+        assert_eq!(rx.recv_buf::<u8>(), Ok(2));
+        assert_eq!(rx.peek_buf::<u8>(), None);
+
+        let (tx, rx) = channel();
+        let mut rx = BufferedReceiver::new(rx.0, OrderedBuffer::new());
+        tx.send(1u8).unwrap();
+        tx.send(String::from("a")).unwrap();
+        tx.send(2u8).unwrap();
+        rx.flush_channel_to_buffer();
+        *rx.peek_buf_mut::<u8>().unwrap() = 5;
+        assert_eq!(rx.peek_buf::<u8>(), Some(&5));
+        assert_eq!(rx.drain_buf::<u8>(), vec![5, 2]);
+    }
+
+    #[test]
+    pub fn recv_with_typeid_test()
+    {
+        let (tx, rx) = channel();
+        tx.send(1u8).unwrap();
+        tx.send(String::from("a")).unwrap();
+        tx.send(2u8).unwrap();
+        assert_eq!(rx.recv_with_typeid::<u8>(), Ok((1, TypeId::of::<u8>())));
+        let (r, type_id) = rx.recv_any_with_typeid().unwrap();
+        assert_eq!(type_id, TypeId::of::<String>());
+        assert_eq!(r.downcast_ref::<String>().map(String::as_str), Some("a"));
+        assert!(matches!(rx.recv_with_typeid::<String>(), Err(AnyRecvError::WrongType(_, _))));
+        drop(tx);
+        assert!(rx.recv_any_with_typeid().is_err());
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn buffered_recv_any_test()
+    {
+        use std::time::Duration;
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1u8).unwrap();
+        tx.send(String::from("a")).unwrap();
+        tx.send('x').unwrap();
+        tx.send(2.5f32).unwrap();
+        assert_eq!(rx.recv_until::<char>(), Ok('x'));
+
+        assert_eq!(rx.buffer_len(), 2);
+        let first = rx.try_recv_any().unwrap();
+        let second = rx.recv_timeout_any(Duration::from_millis(1)).unwrap();
+        let mut types = vec![first.as_ref().type_id(), second.as_ref().type_id()];
+        types.sort();
+        let mut expected = vec![TypeId::of::<u8>(), TypeId::of::<String>()];
+        expected.sort();
+        assert_eq!(types, expected);
+        assert_eq!(rx.buffer_len(), 0);
+
+        assert_eq!(rx.try_recv_any().unwrap().downcast_ref::<f32>(), Some(&2.5));
+        tx.send(3u8).unwrap();
+        assert_eq!(rx.recv_timeout_any(Duration::from_millis(1)).unwrap().downcast_ref::<u8>(), Some(&3));
+        assert!(matches!(
+            rx.recv_timeout_any(Duration::from_millis(1)),
+            Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout))));
+        drop(tx);
+        assert!(matches!(
+            rx.recv_timeout_any(Duration::from_millis(1)),
+            Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Disconnected))));
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn buffer_ttl_test()
+    {
+        use std::{thread, time::Duration};
+
+        let (tx, mut rx) = buffered_channel();
+        let (dead_tx, dead_rx) = channel();
+        rx.set_buffer_ttl(Duration::from_millis(30));
+        rx.set_buffer_ttl_of::<String>(Duration::from_secs(60));
+        rx.expire_to(dead_tx);
+        assert_eq!(rx.buffer_ttl_of::<u8>(), Some(Duration::from_millis(30)));
+        assert_eq!(rx.buffer_ttl_of::<String>(), Some(Duration::from_secs(60)));
+
+        tx.send(1u8).unwrap();
+        tx.send(String::from("kept")).unwrap();
+        rx.flush_channel_to_buffer();
+        thread::sleep(Duration::from_millis(50));
+        tx.send(2u8).unwrap();
+        rx.flush_channel_to_buffer();
+
+        // The stale u8 was evicted when the fresh one was buffered
+        assert_eq!(rx.buffer_len_of::<u8>(), 1);
+        assert_eq!(dead_rx.try_recv::<u8>(), Ok(1));
+        assert_eq!(rx.recv_buf::<u8>(), Ok(2));
+        assert_eq!(rx.evict_expired(), 0);
+        assert_eq!(rx.recv_buf::<String>(), Ok(String::from("kept")));
+
+        let expired = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let on_expire = expired.clone();
+        rx.on_expire(move |type_id, _| on_expire.lock().unwrap().push(type_id));
+        tx.send(3u8).unwrap();
+        tx.send('x').unwrap();
+        assert_eq!(rx.recv_until::<char>(), Ok('x'));
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(rx.buffer_len(), 1);
+        assert_eq!(rx.evict_expired(), 1);
+        assert_eq!(rx.buffer_len(), 0);
+        assert_eq!(*expired.lock().unwrap(), vec![TypeId::of::<u8>()]);
+        assert!(dead_rx.try_recv_any().is_err());
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn buffer_ttl_peek_and_drain_test()
+    {
+        use std::{thread, time::Duration};
+
+        let (tx, mut rx) = buffered_channel();
+        rx.set_buffer_ttl_of::<u8>(Duration::from_millis(100));
+
+        // Peeking evicts expired values, so it shows what recv_buf returns
+        tx.send(1u8).unwrap();
+        rx.flush_channel_to_buffer();
+        thread::sleep(Duration::from_millis(120));
+        assert_eq!(rx.peek_buf::<u8>(), None);
+        assert_eq!(rx.recv_buf::<u8>(), Err(AnyRecvError::EmptyBuffer));
+
+        // A drain iterator's length counts only the values that have not 
+        // expired
+        tx.send(2u8).unwrap();
+        rx.flush_channel_to_buffer();
+        thread::sleep(Duration::from_millis(60));
+        tx.send(3u8).unwrap();
+        rx.flush_channel_to_buffer();
+        thread::sleep(Duration::from_millis(60));
+        let iter = rx.buf_drain_iter::<u8>();
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.collect::<Vec<_>>(), vec![3]);
+
+        tx.send(4u8).unwrap();
+        rx.flush_channel_to_buffer();
+        thread::sleep(Duration::from_millis(120));
+        assert!(rx.drain_buf_all().is_empty());
+    }
+
+    #[test]
+    pub fn spin_recv_test()
+    {
+        use std::{thread, time::Duration};
+
+        let (tx, rx) = channel();
+        let rx = rx.into_spin(SpinFallback::GiveUp);
+        assert!(matches!(rx.spin_recv::<u8>(10), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty))));
+
+        let worker = thread::spawn(move ||
+        {
+            tx.send(1u8).unwrap();
+            tx.send('x').unwrap();
+            tx
+        });
+        assert_eq!(rx.spin_recv::<u8>(u32::MAX), Ok(1));
+        assert!(matches!(rx.spin_recv::<u8>(u32::MAX), Err(AnyRecvError::WrongType(_, _))));
+        let tx = worker.join().unwrap();
+
+        let rx = SpinReceiver::new(rx.into_inner(), SpinFallback::Block);
+        let worker = thread::spawn(move ||
+        {
+            thread::sleep(Duration::from_millis(20));
+            tx.send(2u8).unwrap();
+        });
+        assert_eq!(rx.spin_recv::<u8>(1), Ok(2));
+        worker.join().unwrap();
+        assert!(matches!(rx.spin_recv::<u8>(1), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Disconnected))));
+    }
+
+    #[test]
+    pub fn broadcast_test()
+    {
+        let (senders, receivers): (Vec<_>, Vec<_>) = (0..3).map(|_| channel()).unzip();
+        let results = broadcast(&senders, String::from("all"));
+        assert!(results.iter().all(Result::is_ok));
+        for rx in &receivers
+        {
+            assert_eq!(rx.try_recv::<String>(), Ok(String::from("all")));
+        }
+
+        let mut receivers = receivers;
+        drop(receivers.remove(1));
+        let results = broadcast(&senders, 7u8);
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok() && results[2].is_ok());
+        assert_eq!(results[1].as_ref().unwrap_err().0.downcast_ref::<u8>(), Some(&7));
+        assert!(receivers.iter().all(|rx| rx.try_recv::<u8>() == Ok(7)));
+        assert!(broadcast(&[], 1u8).is_empty());
+    }
+
+    #[cfg(all(feature = "serde", feature = "buf_recv"))]
+    #[test]
+    pub fn snapshot_buffer_test()
+    {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Order
+        {
+            id: u32,
+            item: String
+        }
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        enum Status
+        {
+            Open,
+            Closed(u64)
+        }
+
+        fn consumer() -> (AnySender, BufferedReceiver)
+        {
+            let (tx, mut rx) = buffered_channel();
+            rx.register_serializable::<Order>();
+            rx.register_serializable::<Status>();
+            (tx, rx)
+        }
+
+        let (tx, mut rx) = consumer();
+        tx.send(Order { id: 1, item: String::from("a") }).unwrap();
+        tx.send(Status::Open).unwrap();
+        tx.send(Order { id: 2, item: String::from("b") }).unwrap();
+        tx.send(Status::Closed(9)).unwrap();
+        rx.flush_channel_to_buffer();
+        let snapshot = rx.snapshot_buffer().unwrap();
+        assert_eq!(rx.buffer_len(), 4);
+
+        register_type_name::<f32>();
+        tx.send(0.5f32).unwrap();
+        rx.flush_channel_to_buffer();
+        match rx.snapshot_buffer()
+        {
+            Err(SnapshotError::Unregistered(names)) => assert_eq!(names, vec![String::from("f32")]),
+            other => panic!("unexpected result: {:?}", other)
+        }
+        drop((tx, rx));
+
+        // A restarted consumer picks up where the old one left off
+        let (tx, mut rx) = consumer();
+        tx.send(Status::Open).unwrap();
+        rx.flush_channel_to_buffer();
+        assert_eq!(rx.restore_buffer(&snapshot).unwrap(), 4);
+        assert_eq!(rx.drain_buf::<Order>(), vec![
+            Order { id: 1, item: String::from("a") },
+            Order { id: 2, item: String::from("b") }]);
+        assert_eq!(rx.drain_buf::<Status>(), vec![Status::Open, Status::Open, Status::Closed(9)]);
+
+        let (_tx, mut other) = buffered_channel();
+        other.register_serializable::<Order>();
+        assert!(matches!(other.restore_buffer(&snapshot), Err(SnapshotError::UnknownTag(_))));
+        assert!(matches!(other.restore_buffer(&[1, 2, 3]), Err(SnapshotError::Malformed(_))));
+        assert_eq!(other.buffer_len(), 0);
+    }
+
+    #[test]
+    pub fn recv_or_test()
+    {
+        use std::time::Duration;
+
+        let (tx, rx) = channel();
+        tx.send(1u8).unwrap();
+        tx.send('x').unwrap();
+        assert_eq!(rx.recv_or(9u8), 1);
+        assert_eq!(rx.recv_or(9u8), 9);
+        assert_eq!(rx.recv_timeout_or(Duration::from_millis(1), 9u8), 9);
+        tx.send(2u8).unwrap();
+        assert_eq!(rx.recv_timeout_or(Duration::from_millis(1), 9u8), 2);
+        drop(tx);
+        assert_eq!(rx.recv_or(String::from("gone")), "gone");
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn recv_buf_any_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1u32).unwrap();
+        tx.send(String::from("a")).unwrap();
+        tx.send('x').unwrap();
+        assert_eq!(rx.recv_until::<char>(), Ok('x'));
+
+        let mut values = [rx.recv_buf_any().unwrap(), rx.recv_buf_any().unwrap()];
+        values.sort_by_key(|r| r.is::<String>());
+        assert_eq!(values[0].downcast_ref::<u32>(), Some(&1));
+        assert_eq!(values[1].downcast_ref::<String>().map(String::as_str), Some("a"));
+        assert!(matches!(rx.recv_buf_any(), Err(AnyRecvError::EmptyBuffer)));
+
+        let (tx, rx) = channel();
+        let mut rx = BufferedReceiver::new(rx.0, OrderedBuffer::new());
+        tx.send(String::from("b")).unwrap();
+        tx.send(2u32).unwrap();
+        rx.flush_channel_to_buffer();
+        assert!(rx.recv_buf_any().unwrap().is::<String>());
+        assert!(rx.recv_buf_any().unwrap().is::<u32>());
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn buffered_receiver_from_test()
+    {
+        let (tx, rx) = channel();
+        tx.send(1u8).unwrap();
+        tx.send(String::from("a")).unwrap();
+        let mut rx = BufferedReceiver::from(rx);
+        assert_eq!(rx.recv_until::<String>(), Ok(String::from("a")));
+        assert_eq!(rx.recv::<u8>(), Ok(1));
+
+        let (raw_tx, raw_rx) = mpsc::channel();
+        let tx = AnySender::new(raw_tx);
+        tx.send('x').unwrap();
+        let mut rx: BufferedReceiver = raw_rx.into();
+        assert_eq!(rx.try_recv::<char>(), Ok('x'));
+
+        // A buffer handed over from another receiver is received first
+        tx.send(2u8).unwrap();
+        tx.send(String::from("b")).unwrap();
+        let (old_tx, mut old_rx) = buffered_channel();
+        old_tx.send(3u8).unwrap();
+        old_tx.send('y').unwrap();
+        assert_eq!(old_rx.recv_until::<char>(), Ok('y'));
+        let buf = std::mem::take(&mut old_rx.buf);
+        let mut rx = rx.into_plain().0.into_buffered_with(buf);
+        assert_eq!(rx.buffer_len(), 1);
+        assert_eq!(rx.recv::<u8>(), Ok(3));
+        assert_eq!(rx.recv_until::<String>(), Ok(String::from("b")));
+        assert_eq!(rx.recv::<u8>(), Ok(2));
+    }
+
+    #[test]
+    pub fn downcast_or_err_test()
+    {
+        let (tx, rx) = channel();
+        tx.send(1u32).unwrap();
+        tx.send(String::from("a")).unwrap();
+        assert_eq!(rx.recv_any().and_then(|r| r.downcast_or_err::<u32>()), Ok(1));
+        let err = rx.recv_any().unwrap().downcast_or_err::<u32>().unwrap_err();
+        assert_eq!(err.recover_as::<String>().map(String::as_str), Some("a"));
+        assert!(matches!(err, AnyRecvError::WrongType(type_id, _) if type_id == TypeId::of::<String>()));
+    }
+
+    #[test]
+    pub fn channel_builder_test()
+    {
+        let (tx, rx) = ChannelBuilder::new().build();
+        tx.send(1u8).unwrap();
+        assert_eq!(rx.recv::<u8>(), Ok(1));
+
+        #[cfg(any(feature = "crossbeam", feature = "flume"))]
+        {
+            let (tx, rx) = ChannelBuilder::new().bounded(1).build();
+            tx.send(1u8).unwrap();
+            assert!(tx.0.try_send(Box::new(2u8)).is_err());
+            assert_eq!(rx.recv::<u8>(), Ok(1));
+        }
+
+        #[cfg(feature = "buf_recv")]
+        {
+            let (tx, mut rx) = ChannelBuilder::new()
+                .buffered()
+                .with_buf_capacity(4)
+                .buffer_limit(BufferLimit::per_type(1, OverflowPolicy::DropNewest))
+                .overflow_policy(OverflowPolicy::DropOldest)
+                .build();
+            assert!(rx.buf.capacity() >= 4);
+            assert_eq!(rx.buffer_limit(), BufferLimit::per_type(1, OverflowPolicy::DropOldest));
+            tx.send(1u8).unwrap();
+            tx.send(2u8).unwrap();
+            tx.send('x').unwrap();
+            assert_eq!(rx.recv_until::<char>(), Ok('x'));
+            assert_eq!(rx.recv_buf::<u8>(), Ok(2));
+            assert_eq!(rx.dropped_count(), 1);
+        }
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn buffered_recv_any_order_test()
+    {
+        use std::time::Duration;
+
+        fn send_all(tx: &AnySender)
+        {
+            tx.send(1u8).unwrap();
+            tx.send(String::from("b")).unwrap();
+            tx.send(3u8).unwrap();
+            tx.send('x').unwrap();
+            tx.send(String::from("d")).unwrap();
+        }
+
+        fn describe(r: Box<dyn Any>) -> String
+        {
+            match r.downcast::<u8>()
+            {
+                Ok(t) => t.to_string(),
+                Err(r) => match r.downcast::<String>()
+                {
+                    Ok(t) => *t,
+                    Err(r) => r.downcast::<u16>().unwrap().to_string()
+                }
+            }
+        }
+
+        // Buffered values in send order, then the channel in send order
+        let (tx, rx) = channel();
+        let mut rx = BufferedReceiver::new(rx.0, OrderedBuffer::new());
+        send_all(&tx);
+        assert_eq!(rx.recv_until::<char>(), Ok('x'));
+        tx.send(5u16).unwrap();
+        let values: Vec<String> = vec![
+            rx.recv_any().unwrap(),
+            rx.try_recv_any().unwrap(),
+            rx.recv_timeout_any(Duration::from_millis(1)).unwrap(),
+            rx.recv_any().unwrap(),
+            rx.try_recv_any().unwrap()].into_iter().map(describe).collect();
+        assert_eq!(values, vec!["1", "b", "3", "d", "5"]);
+
+        // The default buffer only keeps the order within each type
+        let (tx, mut rx) = buffered_channel();
+        send_all(&tx);
+        assert_eq!(rx.recv_until::<char>(), Ok('x'));
+        tx.send(5u16).unwrap();
+        let values: Vec<String> = (0..5).map(|_| describe(rx.try_recv_any().unwrap())).collect();
+        let mut buffered = values[..3].to_vec();
+        assert!(buffered.iter().position(|v| v == "1") < buffered.iter().position(|v| v == "3"));
+        buffered.sort();
+        assert_eq!(buffered, vec!["1", "3", "b"]);
+        assert_eq!(values[3..], [String::from("d"), String::from("5")]);
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn recv_all_into_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        tx.send(1u8).unwrap();
+        tx.send(2u8).unwrap();
+        tx.send('x').unwrap();
+        assert_eq!(rx.recv_until::<char>(), Ok('x'));
+        tx.send(String::from("a")).unwrap();
+        tx.send(3u8).unwrap();
+
+        let mut out: Vec<Box<dyn Any>> = vec![Box::new(0u8)];
+        assert_eq!(rx.recv_all_into(&mut out), 4);
+        let values: Vec<_> = out.iter().filter_map(|r| r.downcast_ref::<u8>()).copied().collect();
+        assert_eq!(values, vec![0, 1, 2, 3]);
+        assert!(out[3].is::<String>());
+        assert_eq!(rx.buffer_len(), 0);
+
+        out.clear();
+        assert_eq!(rx.recv_all_into(&mut out), 0);
+        assert!(out.is_empty());
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn recv_until_bounded_test()
+    {
+        let (tx, mut rx) = buffered_channel();
+        let producer = std::thread::spawn(move ||
+        {
+            for i in 0..10_000u32
+            {
+                tx.send(i).unwrap();
+            }
+            tx.send('x').unwrap();
+        });
+        producer.join().unwrap();
+
+        assert_eq!(rx.recv_until_bounded::<char>(100), Err(AnyRecvError::BufferLimitReached(101)));
+        assert_eq!(rx.buffer_len(), 101);
+        assert_eq!(rx.recv_buf::<u32>(), Ok(0));
+        assert_eq!(rx.recv_until_bounded::<u32>(0), Ok(1));
+        rx.clear_buf();
+        assert_eq!(rx.recv_until_bounded::<char>(10_000), Ok('x'));
+        assert_eq!(rx.buffer_len(), 10_000 - 101);
+        assert_eq!(rx.recv_until_bounded::<char>(1), Err(AnyRecvError::RecvError(mpsc::DISCONNECTED)));
+    }
+
+    #[test]
+    pub fn priority_channel_test()
+    {
+        let (tx, mut rx) = priority_channel();
+        rx.set_priority::<u8>(1);
+        rx.set_priority::<char>(5);
+        assert_eq!(rx.priority_of::<i32>(), 0);
+        tx.send(1u8).unwrap();
+        tx.send(-1i32).unwrap();
+        tx.send('a').unwrap();
+        tx.send(2u8).unwrap();
+        tx.send('b').unwrap();
+        assert_eq!(rx.recv_priority().unwrap().downcast_ref::<char>(), Some(&'a'));
+        assert_eq!(rx.len(), 4);
+        assert_eq!(rx.recv_priority().unwrap().downcast_ref::<char>(), Some(&'b'));
+        assert_eq!(rx.recv_priority().unwrap().downcast_ref::<u8>(), Some(&1));
+        assert_eq!(rx.recv_priority().unwrap().downcast_ref::<u8>(), Some(&2));
+        assert_eq!(rx.recv_priority().unwrap().downcast_ref::<i32>(), Some(&-1));
+        assert!(rx.is_empty());
+        assert_eq!(rx.try_recv_priority().unwrap_err(), AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty));
+        tx.send(3u8).unwrap();
+        assert_eq!(rx.recv_priority().unwrap().downcast_ref::<u8>(), Some(&3));
+        drop(tx);
+        assert_eq!(rx.recv_priority().unwrap_err(), AnyRecvError::RecvError(mpsc::DISCONNECTED));
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn dispatch_handlers_test()
+    {
+        use std::sync::{Arc, Mutex};
+        let (tx, mut rx) = buffered_channel();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let ints = seen.clone();
+        rx.on::<i32>(move |i| ints.lock().unwrap().push(format!("i32 {}", i)));
+        let chars = seen.clone();
+        rx.on::<char>(move |c| chars.lock().unwrap().push(format!("char {}", c)));
+        tx.send(1i32).unwrap();
+        tx.send('a').unwrap();
+        tx.send(2u8).unwrap();
+        assert_eq!(rx.dispatch_one(), Ok(true));
+        assert_eq!(rx.dispatch_pending(), 1);
+        assert_eq!(rx.buffer_len(), 1);
+        assert_eq!(rx.recv_buf::<u8>(), Ok(2));
+        assert_eq!(*seen.lock().unwrap(), ["i32 1", "char a"]);
+
+        assert!(rx.off::<char>());
+        assert!(!rx.off::<char>());
+        let other = seen.clone();
+        rx.on_unhandled(move |r| other.lock().unwrap().push(format!("other {:?}", r.as_ref().type_id())));
+        tx.send('b').unwrap();
+        assert_eq!(rx.dispatch_pending(), 1);
+        assert_eq!(seen.lock().unwrap().last().unwrap(), &format!("other {:?}", TypeId::of::<char>()));
+        assert!(rx.off_unhandled());
+
+        rx.on::<&str>(|s| panic!("{}", s));
+        tx.send("boom").unwrap();
+        tx.send(3i32).unwrap();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| rx.dispatch_one()));
+        assert!(result.is_err());
+        drop(tx);
+        assert_eq!(rx.dispatch_blocking_until_disconnect(), 1);
+        assert_eq!(seen.lock().unwrap().last().unwrap(), "i32 3");
+        assert_eq!(rx.dispatch_one(), Err(AnyRecvError::RecvError(mpsc::DISCONNECTED)));
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    pub fn mock_channel_test()
+    {
+        let (tx, rx) = mock_channel();
+        let tx2 = tx.clone();
+        tx.send(1i32).unwrap();
+        tx2.send(String::from("two")).unwrap();
+        tx.send(3i32).unwrap();
+        tx.assert_sent_types(&[TypeId::of::<i32>(), TypeId::of::<String>(), TypeId::of::<i32>()]);
+        assert_eq!(rx.len(), 3);
+        assert_eq!(rx.recv::<i32>(), Ok(1));
+        match rx.recv::<i32>()
+        {
+            Err(err) => assert_eq!(err.into_recovered::<String>(), Ok(String::from("two"))),
+            ok => panic!("expected WrongType, got {:?}", ok)
+        }
+        assert_eq!(rx.recv::<i32>(), Ok(3));
+        assert!(rx.is_empty());
+        assert_eq!(rx.recv::<i32>(), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty)));
+        assert_eq!(tx2.sent_types().len(), 3);
+        drop(tx);
+        drop(tx2);
+        assert_eq!(rx.recv::<i32>(), Err(AnyRecvError::RecvError(mpsc::DISCONNECTED)));
+
+        let (tx, rx) = mock_channel();
+        drop(rx);
+        assert!(tx.send(4u8).is_err());
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn subscription_test()
+    {
+        use std::sync::{Arc, Mutex};
+        let (tx, mut rx) = buffered_channel();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = |name: &'static str| 
+        {
+            let seen = seen.clone();
+            move |i: &i32| seen.lock().unwrap().push(format!("{} {}", name, i))
+        };
+        let a = rx.subscribe(log("a"));
+        tx.send(1i32).unwrap();
+        assert_eq!(rx.dispatch_one(), Ok(true));
+        let b = rx.subscribe(log("b"));
+        tx.send(2i32).unwrap();
+        assert_eq!(rx.dispatch_pending(), 1);
+        assert_eq!(rx.buffer_len(), 0);
+        drop(a);
+        let c = rx.subscribe(log("c"));
+        let owned = Arc::new(Mutex::new(Vec::new()));
+        let taken = owned.clone();
+        rx.on::<i32>(move |i| taken.lock().unwrap().push(i));
+        tx.send(3i32).unwrap();
+        assert_eq!(rx.dispatch_one(), Ok(true));
+        b.unsubscribe();
+        drop(c);
+        assert!(rx.off::<i32>());
+        tx.send(4i32).unwrap();
+        assert_eq!(rx.dispatch_one(), Ok(false));
+        assert_eq!(rx.recv_buf::<i32>(), Ok(4));
+        assert_eq!(*seen.lock().unwrap(), ["a 1", "a 2", "b 2", "b 3", "c 3"]);
+        assert_eq!(*owned.lock().unwrap(), [3]);
+
+        // A subscriber may drop subscriptions, including its own, mid-dispatch.
+        seen.lock().unwrap().clear();
+        let later = Arc::new(Mutex::new(None));
+        let slot = later.clone();
+        let first = rx.subscribe(move |_: &i32| { slot.lock().unwrap().take(); });
+        *later.lock().unwrap() = Some(rx.subscribe(log("later")));
+        let own = Arc::new(Mutex::new(None));
+        let own_slot = own.clone();
+        *own.lock().unwrap() = Some(rx.subscribe(move |_: &i32| { own_slot.lock().unwrap().take(); }));
+        let last = rx.subscribe(log("last"));
+        tx.send(5i32).unwrap();
+        tx.send(6i32).unwrap();
+        assert_eq!(rx.dispatch_pending(), 2);
+        assert_eq!(*seen.lock().unwrap(), ["last 5", "last 6"]);
+        assert!(own.lock().unwrap().is_none());
+        drop(rx);
+        drop(first);
+        drop(last);
+    }
+
+    #[cfg(feature = "test_utils")]
+    #[test]
+    pub fn recording_channel_test()
+    {
+        let (tx, mut rx) = recording_channel();
+        tx.send(1i32).unwrap();
+        tx.send(String::from("two")).unwrap();
+        tx.send('x').unwrap();
+        tx.send(3i32).unwrap();
+        assert_eq!(rx.recv::<i32>(), Ok(1));
+        assert_eq!(rx.recv::<String>(), Ok(String::from("two")));
+        assert!(rx.recv::<i32>().is_err());
+        assert_eq!(rx.try_recv::<i32>(), Ok(3));
+        assert_eq!(rx.recorded_len(), 3);
+        for _ in 0..2
+        {
+            let replay = rx.playback();
+            assert_eq!(replay.recv::<i32>(), Ok(1));
+            assert_eq!(replay.recv::<String>(), Ok(String::from("two")));
+            assert_eq!(replay.recv::<i32>(), Ok(3));
+            assert_eq!(replay.recv::<i32>(), Err(AnyRecvError::RecvError(mpsc::DISCONNECTED)));
+        }
+        rx.clear_recording();
+        assert_eq!(rx.playback().try_recv::<i32>(), Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Disconnected)));
+    }
+
+    #[cfg(feature = "buf_recv")]
+    #[test]
+    pub fn register_type_name_test()
+    {
+        struct NeverSent;
+        let err = AnyRecvError::BufRecvError(TypeId::of::<NeverSent>());
+        assert_eq!(err.to_string(), format!("Received wrong type: {:?}", TypeId::of::<NeverSent>()));
+        register_type_name::<NeverSent>();
+        assert!(err.to_string().contains("NeverSent"));
+        assert_eq!(err.received_type_name(), Some(std::any::type_name::<NeverSent>()));
+        let full = AnyRecvError::BufferFull(TypeId::of::<NeverSent>());
+        assert!(full.to_string().ends_with("NeverSent"));
+    }
+
+    #[cfg(all(feature = "metrics", feature = "buf_recv"))]
+    #[test]
+    pub fn recv_stats_test()
+    {
+        register_type_name::<u8>();
+        register_type_name::<char>();
+        let (tx, mut rx) = buffered_channel_with(2, OverflowPolicy::DropNewest);
+        for i in 0..3u8
+        {
+            tx.send(i).unwrap();
+        }
+        tx.send('a').unwrap();
+        tx.send(7i32).unwrap();
+        drop(tx);
+        assert_eq!(rx.recv_until::<i32>(), Ok(7));
+        assert_eq!(rx.recv::<u8>(), Ok(0));
+        assert_eq!(rx.recv_nobuf::<i32>().unwrap_err(), AnyRecvError::RecvError(mpsc::DISCONNECTED));
+        let stats = rx.stats();
+        assert_eq!(stats.received, 5);
+        assert_eq!(stats.buffered, 1);
+        assert_eq!(stats.high_water, 2);
+        let of = |type_id: TypeId| stats.types.iter().find(|t| t.type_id == type_id).unwrap().clone();
+        let u8_stats = of(TypeId::of::<u8>());
+        assert_eq!((u8_stats.received, u8_stats.spilled, u8_stats.dropped, u8_stats.buffered), (1, 2, 1, 1));
+        assert_eq!(u8_stats.name, Some("u8"));
+        let char_stats = of(TypeId::of::<char>());
+        assert_eq!((char_stats.received, char_stats.spilled, char_stats.dropped, char_stats.buffered), (0, 0, 1, 0));
+        let i32_stats = of(TypeId::of::<i32>());
+        assert_eq!((i32_stats.received, i32_stats.spilled, i32_stats.dropped, i32_stats.buffered), (1, 0, 0, 0));
+        let table = stats.to_string();
+        assert!(table.starts_with("received from channel: 5\nbuffered: 1 (high-water mark 2)\n"));
+        assert!(table.lines().any(|line| line.starts_with("char") && line.ends_with("|        0")));
+        assert_eq!(table.lines().count(), 6);
+    }
 }
@@ -0,0 +1,122 @@
+use super::{mpsc, AnyReceiver, CancelToken, ReceiverExt};
+use core::any::*;
+use std::{cell::Cell, fmt, time::Duration};
+
+/// A single value taken off a channel early, e.g. to probe its state, which
+/// must be handed out before anything else on that channel.
+///
+/// This is the only mutable state an [AnyReceiver] keeps for its channel. A
+/// [Cell] lets probes such as [AnyReceiver::ready] take `&self` like every 
+/// other receive; it makes the receiver `!Sync`, which the underlying 
+/// receiver already is for the default backend. One slot is enough because 
+/// a probe only ever looks at the next value, and every receive checks it 
+/// first, at the cost of a take per receive. Anything that needs more than
+/// one value held back should use a 
+/// [BufferedReceiver](crate::BufferedReceiver) instead.
+#[derive(Default)]
+pub(crate) struct Lookahead(Cell<Option<Box<dyn Any>>>);
+
+impl Lookahead
+{
+    #[inline]
+    pub(crate) fn take(&self) -> Option<Box<dyn Any>>
+    {
+        self.0.take()
+    }
+
+    /// Stores `r` to be handed out next. The slot must be empty.
+    #[inline]
+    pub(crate) fn put(&self, r: Box<dyn Any>)
+    {
+        debug_assert!(!self.is_some(), "Lookahead slot already occupied");
+        self.0.set(Some(r));
+    }
+
+    #[inline]
+    pub(crate) fn is_some(&self) -> bool
+    {
+        self.type_id().is_some()
+    }
+
+    /// The [TypeId] of the held value, if any.
+    #[inline]
+    pub(crate) fn type_id(&self) -> Option<TypeId>
+    {
+        let r = self.0.take();
+        let type_id = r.as_ref().map(|r| r.as_ref().type_id());
+        self.0.set(r);
+        type_id
+    }
+}
+
+impl fmt::Debug for Lookahead
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_tuple("Lookahead")
+            .field(&self.is_some())
+            .finish()
+    }
+}
+
+/// Receive operations on an [AnyReceiver] hand out the lookahead value, if 
+/// there is one, before touching the channel. Everything handed out is 
+/// copied to the tap.
+impl ReceiverExt for AnyReceiver
+{
+    #[inline]
+    fn recv_msg(&self) -> Result<Box<dyn Any>, mpsc::RecvError>
+    {
+        let r = match self.1.take()
+        {
+            Some(r) => r,
+            None => self.0.recv_msg()?
+        };
+        self.3.copy(r.as_ref());
+        Ok(r)
+    }
+
+    #[inline]
+    fn try_recv_msg(&self) -> Result<Box<dyn Any>, mpsc::TryRecvError>
+    {
+        let r = match self.1.take()
+        {
+            Some(r) => r,
+            None => self.0.try_recv_msg()?
+        };
+        self.3.copy(r.as_ref());
+        Ok(r)
+    }
+
+    #[inline]
+    fn recv_timeout_msg(&self, timeout: Duration) -> Result<Box<dyn Any>, mpsc::RecvTimeoutError>
+    {
+        let r = match self.1.take()
+        {
+            Some(r) => r,
+            None => self.0.recv_timeout_msg(timeout)?
+        };
+        self.3.copy(r.as_ref());
+        Ok(r)
+    }
+
+    #[inline]
+    fn recv_msg_cancellable(&self, token: &CancelToken) -> Result<Option<Box<dyn Any>>, mpsc::RecvError>
+    {
+        if token.is_cancelled()
+        {
+            return Ok(None);
+        }
+        let r = match self.1.take()
+        {
+            Some(r) => r,
+            None => match self.0.recv_msg_cancellable(token)?
+            {
+                Some(r) => r,
+                None => return Ok(None)
+            }
+        };
+        self.3.copy(r.as_ref());
+        Ok(Some(r))
+    }
+}
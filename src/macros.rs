@@ -0,0 +1,57 @@
+/// Receives one value and runs the first arm whose type matches it.
+///
+/// The source may be an [AnyReceiver](crate::AnyReceiver) or a 
+/// [BufferedReceiver](crate::BufferedReceiver); for the latter, buffered 
+/// values are taken first. Each arm binds the downcast value to a name. An 
+/// optional final `_` arm handles values of any other type. The whole 
+/// expression evaluates to a `Result` holding the value of the arm that ran, 
+/// or an [AnyRecvError](crate::AnyRecvError) if the receive failed. Without 
+/// a `_` arm, a value of any other type is returned in a 
+/// [AnyRecvError::WrongType](crate::AnyRecvError::WrongType).
+///
+/// ```
+/// # use any_mpsc::any_dispatch;
+/// let (tx, rx) = any_mpsc::channel();
+/// tx.send(String::from("hello")).unwrap();
+/// let len = any_dispatch!(rx => {
+///     i32 as i => i as usize,
+///     String as s => s.len(),
+///     _ => 0
+/// });
+/// assert_eq!(len.unwrap(), 5);
+/// ```
+#[macro_export]
+macro_rules! any_dispatch
+{
+    (@arms $value:ident; _ => $default:expr $(,)?) =>
+    {
+        {
+            drop($value);
+            Ok($default)
+        }
+    };
+    (@arms $value:ident; $ty:ty as $name:ident => $body:expr $(, $($rest:tt)*)?) =>
+    {
+        match $value.downcast::<$ty>()
+        {
+            Ok($name) =>
+            {
+                let $name = *$name;
+                Ok($body)
+            },
+            Err($value) => $crate::any_dispatch!(@arms $value; $($($rest)*)?)
+        }
+    };
+    (@arms $value:ident;) =>
+    {
+        Err($crate::AnyRecvError::wrong_type($value))
+    };
+    ($rx:expr => { $($arms:tt)* }) =>
+    {
+        match $rx.recv_any()
+        {
+            Ok(value) => $crate::any_dispatch!(@arms value; $($arms)*),
+            Err(err) => Err(err)
+        }
+    };
+}
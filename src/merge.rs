@@ -0,0 +1,120 @@
+use super::{mpsc, select, AnySender, AnyReceiver, AnyRecvError};
+#[cfg(feature = "buf_recv")]
+use super::BufferedReceiver;
+use core::any::*;
+use std::{cell::Cell, thread, time::Duration};
+
+/// Merges several [AnyReceiver]s into one [MergedReceiver], which receives 
+/// from whichever of them has a message ready.
+#[inline]
+pub fn merge(receivers: Vec<AnyReceiver>) -> MergedReceiver
+{
+    MergedReceiver { receivers, next: Cell::new(0) }
+}
+
+/// Merges several [AnyReceiver]s into one plain [AnyReceiver]. A background
+/// thread receives from the sources as a [MergedReceiver] does and forwards
+/// every value. Values from one source keep their order; there is no order 
+/// across sources. The returned receiver disconnects once every source has
+/// disconnected. If it is dropped first, the thread stops after the next 
+/// value arrives.
+pub fn merge_receivers(receivers: Vec<AnyReceiver>) -> AnyReceiver
+{
+    let merged = merge(receivers);
+    let (tx, rx) = mpsc::channel();
+    let tx = AnySender::new(tx);
+    thread::spawn(move ||
+    {
+        while let Ok(r) = merged.recv_any()
+        {
+            if tx.0.send(r).is_err()
+            {
+                break;
+            }
+        }
+    });
+    AnyReceiver::new(rx)
+}
+
+/// Like [merge_receivers], but returns a [BufferedReceiver].
+#[cfg(feature = "buf_recv")]
+#[inline]
+pub fn merge_buffered_receivers(receivers: Vec<AnyReceiver>) -> BufferedReceiver
+{
+    merge_receivers(receivers).into_buffered()
+}
+
+/// Several [AnyReceiver]s read as one. Sources are polled round-robin in the
+/// same way as [Select](crate::Select), so a source that always has 
+/// messages ready cannot starve the others. Reports disconnection once every
+/// source is disconnected. Created with [merge].
+#[derive(Debug)]
+pub struct MergedReceiver
+{
+    pub receivers: Vec<AnyReceiver>,
+    next: Cell<usize>
+}
+
+impl MergedReceiver
+{
+    /// Like [AnyReceiver::recv], but receives from whichever source is ready.
+    #[inline]
+    pub fn recv<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        self.recv_any().and_then(downcast)
+    }
+
+    /// Like [AnyReceiver::recv_timeout], but receives from whichever source
+    /// is ready.
+    #[inline]
+    pub fn recv_timeout<T: 'static>(&self, timeout: Duration) -> Result<T, AnyRecvError>
+    {
+        self.with_next(|receivers, next| select::select_timeout_in(receivers, next, timeout))
+            .and_then(downcast)
+    }
+
+    /// Like [AnyReceiver::try_recv], but receives from whichever source is 
+    /// ready.
+    #[inline]
+    pub fn try_recv<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        self.try_recv_any().and_then(downcast)
+    }
+
+    /// Like [AnyReceiver::recv_any], but receives from whichever source is 
+    /// ready.
+    #[inline]
+    pub fn recv_any(&self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        self.with_next(select::select_in)
+    }
+
+    /// Like [AnyReceiver::try_recv_any], but receives from whichever source 
+    /// is ready.
+    #[inline]
+    pub fn try_recv_any(&self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        self.with_next(select::try_select_in)
+    }
+
+    #[inline]
+    fn with_next(
+        &self, 
+        f: impl FnOnce(&[AnyReceiver], &mut usize) -> Result<(usize, Box<dyn Any>), AnyRecvError>) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        let mut next = self.next.get();
+        let result = f(&self.receivers, &mut next);
+        self.next.set(next);
+        result.map(|(_index, r)| r)
+    }
+}
+
+#[inline]
+fn downcast<T: 'static>(r: Box<dyn Any>) -> Result<T, AnyRecvError>
+{
+    match r.downcast()
+    {
+        Ok(r) => Ok(*r),
+        Err(r) => Err(AnyRecvError::wrong_type(r)),
+    }
+}
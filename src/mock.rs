@@ -0,0 +1,137 @@
+use super::{mpsc, AnyRecvError};
+use core::any::*;
+use std::{collections::VecDeque, sync::{Arc, Mutex, MutexGuard, PoisonError}};
+
+/// A channel for tests that keeps its values in a shared queue instead of
+/// an [mpsc::channel], so that sending and receiving never block and no
+/// other thread is needed. The sender also records the type of every value
+/// it sends. See [MockSender] and [MockReceiver].
+#[inline]
+pub fn mock_channel() -> (MockSender, MockReceiver)
+{
+    let state = Arc::new(Mutex::new(MockState { senders: 1, receiver: true, ..MockState::default() }));
+    (MockSender(state.clone()), MockReceiver(state))
+}
+
+#[derive(Debug, Default)]
+struct MockState
+{
+    queue: VecDeque<Box<dyn Any + Send>>,
+    sent: Vec<TypeId>,
+    senders: usize,
+    receiver: bool
+}
+
+#[inline]
+fn lock(state: &Mutex<MockState>) -> MutexGuard<'_, MockState>
+{
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The sending end of a [mock_channel], with the same `send` as
+/// [AnySender](crate::AnySender).
+#[derive(Debug)]
+pub struct MockSender(Arc<Mutex<MockState>>);
+
+impl MockSender
+{
+    /// Queues `t` for the [MockReceiver] and records its type. Fails like
+    /// [AnySender::send](crate::AnySender::send) if the receiver has been
+    /// dropped.
+    pub fn send<T: Any + Send>(&self, t: T) -> Result<(), mpsc::SendError<Box<dyn Any>>>
+    {
+        let mut state = lock(&self.0);
+        if !state.receiver
+        {
+            return Err(mpsc::SendError(Box::new(t)));
+        }
+        state.sent.push(TypeId::of::<T>());
+        state.queue.push_back(Box::new(t));
+        Ok(())
+    }
+
+    /// The type of every value sent so far by any clone of this sender, in
+    /// the order they were sent, including those already received.
+    #[inline]
+    pub fn sent_types(&self) -> Vec<TypeId>
+    {
+        lock(&self.0).sent.clone()
+    }
+
+    /// Panics unless the values sent so far had exactly the types in
+    /// `expected`, in that order.
+    #[track_caller]
+    pub fn assert_sent_types(&self, expected: &[TypeId])
+    {
+        assert_eq!(self.sent_types(), expected, "mock channel sent different types");
+    }
+}
+
+impl Clone for MockSender
+{
+    fn clone(&self) -> Self
+    {
+        lock(&self.0).senders += 1;
+        MockSender(self.0.clone())
+    }
+}
+
+impl Drop for MockSender
+{
+    fn drop(&mut self)
+    {
+        lock(&self.0).senders -= 1;
+    }
+}
+
+/// The receiving end of a [mock_channel]. Receives values in the order they
+/// were sent, like [AnyReceiver](crate::AnyReceiver), but never blocks.
+#[derive(Debug)]
+pub struct MockReceiver(Arc<Mutex<MockState>>);
+
+impl MockReceiver
+{
+    /// Like [AnyReceiver::recv](crate::AnyReceiver::recv), but an empty
+    /// queue gives [mpsc::TryRecvError::Empty] rather than blocking, since
+    /// nothing could be sent in the meantime. Once the queue is empty and
+    /// every sender has been dropped, gives [mpsc::RecvError].
+    pub fn recv<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        self.recv_any().and_then(|r| r.downcast().map(|t| *t).map_err(AnyRecvError::wrong_type))
+    }
+
+    /// Like [MockReceiver::recv], but returns the value without downcasting
+    /// it.
+    pub fn recv_any(&self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        let mut state = lock(&self.0);
+        match state.queue.pop_front()
+        {
+            Some(r) => Ok(r),
+            None if state.senders == 0 => Err(AnyRecvError::RecvError(mpsc::DISCONNECTED)),
+            None => Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty))
+        }
+    }
+
+    /// Number of values sent but not yet received.
+    #[inline]
+    pub fn len(&self) -> usize
+    {
+        lock(&self.0).queue.len()
+    }
+
+    /// Whether every value sent has been received.
+    #[inline]
+    pub fn is_empty(&self) -> bool
+    {
+        lock(&self.0).queue.is_empty()
+    }
+}
+
+impl Drop for MockReceiver
+{
+    fn drop(&mut self)
+    {
+        lock(&self.0).receiver = false;
+    }
+}
@@ -0,0 +1,129 @@
+use super::AnyBuffer;
+use core::any::*;
+use std::collections::VecDeque;
+
+/// An [AnyBuffer] that keeps every value in one queue, preserving the order
+/// values arrived in across all types. Unlike a 
+/// [TypeBuffer](crate::TypeBuffer), finding a value of a given type takes 
+/// time linear in the number of buffered values.
+#[derive(Debug, Default)]
+pub struct OrderedBuffer
+{
+    values: VecDeque<(TypeId, Box<dyn Any>)>
+}
+
+impl OrderedBuffer
+{
+    /// Creates an empty buffer.
+    #[inline]
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+}
+
+impl AnyBuffer for OrderedBuffer
+{
+    #[inline]
+    fn insert_dyn(&mut self, t: Box<dyn Any>)
+    {
+        self.values.push_back((t.as_ref().type_id(), t));
+    }
+
+    /// The type of the oldest value that passes `pred`.
+    #[inline]
+    fn find_type(&self, pred: &dyn Fn(TypeId) -> bool) -> Option<TypeId>
+    {
+        self.values
+            .iter()
+            .map(|(type_id, _)| *type_id)
+            .find(|type_id| pred(*type_id))
+    }
+
+    fn remove_where_dyn(&mut self, type_id: TypeId, pred: &dyn Fn(&dyn Any) -> bool) -> Option<Box<dyn Any>>
+    {
+        let index = self.values
+            .iter()
+            .position(|(t_id, t)| *t_id == type_id && pred(t.as_ref()))?;
+        self.values.remove(index).map(|(_, t)| t)
+    }
+
+    #[inline]
+    fn iter_dyn(&self, type_id: TypeId) -> Box<dyn Iterator<Item = &dyn Any> + '_>
+    {
+        Box::new(self.values
+            .iter()
+            .filter(move |(t_id, _)| *t_id == type_id)
+            .map(|(_, t)| t.as_ref()))
+    }
+
+    #[inline]
+    fn peek_dyn(&self, type_id: TypeId) -> Option<&dyn Any>
+    {
+        self.values
+            .iter()
+            .find(|(t_id, _)| *t_id == type_id)
+            .map(|(_, t)| t.as_ref())
+    }
+
+    #[inline]
+    fn peek_dyn_mut(&mut self, type_id: TypeId) -> Option<&mut dyn Any>
+    {
+        self.values
+            .iter_mut()
+            .find(|(t_id, _)| *t_id == type_id)
+            .map(|(_, t)| t.as_mut())
+    }
+
+    #[inline]
+    fn len(&self) -> usize
+    {
+        self.values.len()
+    }
+
+    #[inline]
+    fn len_of(&self, type_id: TypeId) -> usize
+    {
+        self.values
+            .iter()
+            .filter(|(t_id, _)| *t_id == type_id)
+            .count()
+    }
+
+    #[inline]
+    fn clear(&mut self)
+    {
+        self.values.clear();
+    }
+
+    fn clear_of(&mut self, type_id: TypeId) -> usize
+    {
+        let len = self.values.len();
+        self.values.retain(|(t_id, _)| *t_id != type_id);
+        len - self.values.len()
+    }
+
+    #[inline]
+    fn retain(&mut self, pred: &mut dyn FnMut(TypeId, &dyn Any) -> bool)
+    {
+        self.values.retain(|(type_id, t)| pred(*type_id, t.as_ref()));
+    }
+
+    fn remove_any_where(&mut self, pred: &dyn Fn(&dyn Any) -> bool) -> Option<Box<dyn Any>>
+    {
+        let index = self.values
+            .iter()
+            .position(|(_, t)| pred(t.as_ref()))?;
+        self.values.remove(index).map(|(_, t)| t)
+    }
+
+    /// Removes and returns every buffered value, in the order they arrived.
+    #[inline]
+    fn drain(&mut self) -> Vec<Box<dyn Any>>
+    {
+        self.values
+            .drain(..)
+            .map(|(_, t)| t)
+            .collect()
+    }
+}
@@ -0,0 +1,127 @@
+use super::{mpsc, AnySender, AnyReceiver, AnyRecvError};
+use core::any::*;
+use std::{sync::{Arc, atomic::{AtomicBool, Ordering}}, thread, time::Duration};
+
+/// An [mpsc::channel] that supports dynamic typing and is poisoned if a 
+/// thread panics while holding a sender, in the same way a [Mutex] is 
+/// poisoned if a thread panics while holding its lock.
+///
+/// [Mutex]: std::sync::Mutex
+#[inline]
+pub fn poisonable_channel() -> (PoisonableAnySender, PoisonableAnyReceiver)
+{
+    let (tx, rx) = super::channel();
+    let poisoned = Arc::new(AtomicBool::new(false));
+    (PoisonableAnySender(tx, poisoned.clone()), PoisonableAnyReceiver(rx, poisoned))
+}
+
+/// Wraps an [AnySender]. If a clone is dropped while its thread is 
+/// panicking, the channel is poisoned and every later receive returns 
+/// [AnyRecvError::Poisoned].
+#[derive(Debug, Clone)]
+pub struct PoisonableAnySender(pub AnySender, Arc<AtomicBool>);
+
+impl PoisonableAnySender
+{
+    /// Wraps [AnySender::send].
+    #[inline]
+    pub fn send<T: Any>(&self, t: T) -> Result<(), mpsc::SendError<Box<dyn Any>>>
+    {
+        self.0.send(t)
+    }
+
+    /// Poisons the channel without panicking.
+    #[inline]
+    pub fn poison(&self)
+    {
+        self.1.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the channel is poisoned.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool
+    {
+        self.1.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for PoisonableAnySender
+{
+    fn drop(&mut self)
+    {
+        if thread::panicking()
+        {
+            self.poison();
+        }
+    }
+}
+
+/// Wraps an [AnyReceiver], checking before each receive whether the channel
+/// has been poisoned by a [PoisonableAnySender].
+#[derive(Debug)]
+pub struct PoisonableAnyReceiver(pub AnyReceiver, Arc<AtomicBool>);
+
+impl PoisonableAnyReceiver
+{
+    /// Wraps [AnyReceiver::recv], returning [AnyRecvError::Poisoned] if the 
+    /// channel is poisoned. A sender that panics while this is blocked is 
+    /// only noticed once the receive returns, usually once every other 
+    /// sender has disconnected.
+    #[inline]
+    pub fn recv<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        self.check()?;
+        self.0.recv()
+    }
+
+    /// Wraps [AnyReceiver::recv_timeout], returning [AnyRecvError::Poisoned]
+    /// if the channel is poisoned.
+    #[inline]
+    pub fn recv_timeout<T: 'static>(&self, timeout: Duration) -> Result<T, AnyRecvError>
+    {
+        self.check()?;
+        self.0.recv_timeout(timeout)
+    }
+
+    /// Wraps [AnyReceiver::try_recv], returning [AnyRecvError::Poisoned] if 
+    /// the channel is poisoned.
+    #[inline]
+    pub fn try_recv<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        self.check()?;
+        self.0.try_recv()
+    }
+
+    /// Wraps [AnyReceiver::recv_any], returning [AnyRecvError::Poisoned] if 
+    /// the channel is poisoned.
+    #[inline]
+    pub fn recv_any(&self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        self.check()?;
+        self.0.recv_any()
+    }
+
+    /// Whether the channel is poisoned.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool
+    {
+        self.1.load(Ordering::SeqCst)
+    }
+
+    /// Clears the poisoned state, so that receives work again.
+    #[inline]
+    pub fn clear_poison(&self)
+    {
+        self.1.store(false, Ordering::SeqCst);
+    }
+
+    #[inline]
+    fn check(&self) -> Result<(), AnyRecvError>
+    {
+        match self.is_poisoned()
+        {
+            true => Err(AnyRecvError::Poisoned),
+            false => Ok(())
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use super::{mpsc, AnyReceiver, AnyRecvError, AnySender};
+use core::any::*;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// An [mpsc::channel](std::sync::mpsc::channel) that supports dynamic typing
+/// and delivers higher-priority types first. See [PriorityReceiver].
+#[inline]
+pub fn priority_channel() -> (AnySender, PriorityReceiver)
+{
+    let (tx, rx) = super::channel();
+    (tx, PriorityReceiver::new(rx))
+}
+
+/// Receives values in order of the priority registered for their type,
+/// highest first, with values of equal priority in the order they arrived.
+/// Types without a registered priority have priority `0`. Values are moved
+/// off the channel into per-priority bins as they are looked for.
+#[derive(Debug)]
+pub struct PriorityReceiver
+{
+    pub rx: AnyReceiver,
+    priorities: HashMap<TypeId, u8>,
+    bins: BTreeMap<u8, VecDeque<Box<dyn Any>>>
+}
+
+impl PriorityReceiver
+{
+    /// Wraps `rx`. Registered casters and any tap are kept.
+    #[inline]
+    pub fn new(rx: AnyReceiver) -> Self
+    {
+        PriorityReceiver { rx, priorities: HashMap::new(), bins: BTreeMap::new() }
+    }
+
+    /// Gives values of type `T` priority `p`. Values already binned keep the
+    /// priority they were binned with.
+    #[inline]
+    pub fn set_priority<T: 'static>(&mut self, p: u8)
+    {
+        self.priorities.insert(TypeId::of::<T>(), p);
+    }
+
+    /// The priority of values of type `T`.
+    #[inline]
+    pub fn priority_of<T: 'static>(&self) -> u8
+    {
+        self.priorities.get(&TypeId::of::<T>()).copied().unwrap_or(0)
+    }
+
+    /// Bins every value waiting on the channel, then returns the oldest
+    /// value of the highest priority. If there are none, blocks until a
+    /// value arrives and returns it.
+    pub fn recv_priority(&mut self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        self.fill();
+        match self.pop()
+        {
+            Some(r) => Ok(r),
+            None => self.rx.recv_any()
+        }
+    }
+
+    /// Like [PriorityReceiver::recv_priority], but never blocks.
+    pub fn try_recv_priority(&mut self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        self.fill();
+        self.pop().ok_or(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty))
+    }
+
+    /// Number of binned values.
+    #[inline]
+    pub fn len(&self) -> usize
+    {
+        self.bins.values().map(VecDeque::len).sum()
+    }
+
+    /// Whether no values are binned.
+    #[inline]
+    pub fn is_empty(&self) -> bool
+    {
+        self.bins.is_empty()
+    }
+
+    fn fill(&mut self)
+    {
+        while let Ok(r) = self.rx.try_recv_any()
+        {
+            let p = self.priorities.get(&r.as_ref().type_id()).copied().unwrap_or(0);
+            self.bins.entry(p).or_default().push_back(r);
+        }
+    }
+
+    fn pop(&mut self) -> Option<Box<dyn Any>>
+    {
+        let mut bin = self.bins.last_entry()?;
+        let r = bin.get_mut().pop_front();
+        if bin.get().is_empty()
+        {
+            bin.remove();
+        }
+        r
+    }
+}
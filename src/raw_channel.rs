@@ -0,0 +1,113 @@
+use super::mpsc::{self, Receiver};
+use core::{any::*, fmt, time::Duration};
+
+/// The receiving half of a channel of boxed values, as used by
+/// [BufferedReceiver](crate::BufferedReceiver). The [mpsc::Receiver]
+/// implementation is used by default; implement this to buffer over some
+/// other transport, such as an in-memory queue. The trait only uses `core`
+/// and `alloc` types, so an implementation does not depend on the `mpsc`
+/// backend. The rest of the crate still requires `std`.
+///
+/// A `BufferedReceiver` over any channel other than [mpsc::Receiver] is not
+/// `Send`.
+pub trait RawChannel
+{
+    /// Blocks until a value arrives or the channel disconnects.
+    fn recv(&self) -> Result<Box<dyn Any>, RawRecvError>;
+
+    /// Returns a value if one is waiting, without blocking.
+    fn try_recv(&self) -> Result<Box<dyn Any>, RawRecvError>;
+
+    /// Blocks until a value arrives, the channel disconnects or `timeout`
+    /// elapses.
+    fn recv_timeout(&self, timeout: Duration) -> Result<Box<dyn Any>, RawRecvError>;
+}
+
+/// Why a [RawChannel] receive returned no value. Receives on a
+/// [BufferedReceiver](crate::BufferedReceiver) report it as the matching
+/// error of the channel backend, such as
+/// [TryRecvError::Empty](std::sync::mpsc::TryRecvError::Empty).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RawRecvError
+{
+    /// No value is waiting. Only returned by [RawChannel::try_recv].
+    Empty,
+    /// The timeout elapsed first. Only returned by
+    /// [RawChannel::recv_timeout].
+    Timeout,
+    /// Every sender has been dropped and no value is waiting.
+    Disconnected
+}
+
+impl RawRecvError
+{
+    #[inline]
+    pub(crate) fn into_recv_error(self) -> mpsc::RecvError
+    {
+        mpsc::DISCONNECTED
+    }
+
+    #[inline]
+    pub(crate) fn into_try_recv_error(self) -> mpsc::TryRecvError
+    {
+        match self
+        {
+            RawRecvError::Empty => mpsc::TryRecvError::Empty,
+            _ => mpsc::TryRecvError::Disconnected
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_recv_timeout_error(self) -> mpsc::RecvTimeoutError
+    {
+        match self
+        {
+            RawRecvError::Timeout => mpsc::RecvTimeoutError::Timeout,
+            _ => mpsc::RecvTimeoutError::Disconnected
+        }
+    }
+}
+
+impl fmt::Display for RawRecvError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            RawRecvError::Empty => f.write_str("receiving on an empty channel"),
+            RawRecvError::Timeout => f.write_str("timed out waiting on channel"),
+            RawRecvError::Disconnected => f.write_str("receiving on a closed channel")
+        }
+    }
+}
+
+impl std::error::Error for RawRecvError {}
+
+impl RawChannel for Receiver<Box<dyn Any>>
+{
+    #[inline]
+    fn recv(&self) -> Result<Box<dyn Any>, RawRecvError>
+    {
+        Receiver::recv(self).map_err(|_| RawRecvError::Disconnected)
+    }
+
+    #[inline]
+    fn try_recv(&self) -> Result<Box<dyn Any>, RawRecvError>
+    {
+        Receiver::try_recv(self).map_err(|err| match err
+        {
+            mpsc::TryRecvError::Empty => RawRecvError::Empty,
+            _ => RawRecvError::Disconnected
+        })
+    }
+
+    #[inline]
+    fn recv_timeout(&self, timeout: Duration) -> Result<Box<dyn Any>, RawRecvError>
+    {
+        Receiver::recv_timeout(self, timeout).map_err(|err| match err
+        {
+            mpsc::RecvTimeoutError::Timeout => RawRecvError::Timeout,
+            _ => RawRecvError::Disconnected
+        })
+    }
+}
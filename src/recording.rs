@@ -0,0 +1,112 @@
+use super::{tap::{clone_boxed, Cloner}, AnyReceiver, AnyRecvError, AnySender};
+use core::any::*;
+use std::{fmt, time::Duration};
+
+/// An [mpsc::channel](std::sync::mpsc::channel) that supports dynamic typing
+/// and records what it receives so that it can be replayed. See
+/// [RecordingReceiver].
+#[inline]
+pub fn recording_channel() -> (AnySender, RecordingReceiver)
+{
+    let (tx, rx) = super::channel();
+    (tx, RecordingReceiver::new(rx))
+}
+
+/// Wraps an [AnyReceiver], keeping a copy of every value it successfully
+/// receives. [RecordingReceiver::playback] replays them in the same order,
+/// e.g. to repeat a sequence captured from a real run in a regression test.
+/// Values handed back in [AnyRecvError::WrongType] are not recorded.
+pub struct RecordingReceiver
+{
+    pub rx: AnyReceiver,
+    log: Vec<(Box<dyn Any + Send>, Cloner)>
+}
+
+impl RecordingReceiver
+{
+    /// Wraps `rx` with an empty recording.
+    #[inline]
+    pub fn new(rx: AnyReceiver) -> Self
+    {
+        RecordingReceiver { rx, log: Vec::new() }
+    }
+
+    /// Wraps [AnyReceiver::recv], recording a clone of the value.
+    #[inline]
+    pub fn recv<T: Clone + Send + 'static>(&mut self) -> Result<T, AnyRecvError>
+    {
+        let result = self.rx.recv();
+        self.record(result)
+    }
+
+    /// Wraps [AnyReceiver::recv_timeout], recording a clone of the value.
+    #[inline]
+    pub fn recv_timeout<T: Clone + Send + 'static>(&mut self, timeout: Duration) -> Result<T, AnyRecvError>
+    {
+        let result = self.rx.recv_timeout(timeout);
+        self.record(result)
+    }
+
+    /// Wraps [AnyReceiver::try_recv], recording a clone of the value.
+    #[inline]
+    pub fn try_recv<T: Clone + Send + 'static>(&mut self) -> Result<T, AnyRecvError>
+    {
+        let result = self.rx.try_recv();
+        self.record(result)
+    }
+
+    /// Creates a receiver on a new channel that already holds a copy of every
+    /// recorded value, in the order they were received. Its sender is
+    /// dropped, so it disconnects once they have all been received.
+    pub fn playback(&self) -> AnyReceiver
+    {
+        let (tx, rx) = super::channel();
+        for (t, cloner) in &self.log
+        {
+            let _ = tx.send_boxed(cloner(t.as_ref()));
+        }
+        rx
+    }
+
+    /// Number of recorded values.
+    #[inline]
+    pub fn recorded_len(&self) -> usize
+    {
+        self.log.len()
+    }
+
+    /// Forgets every recorded value.
+    #[inline]
+    pub fn clear_recording(&mut self)
+    {
+        self.log.clear();
+    }
+
+    /// Unwraps the [AnyReceiver], discarding the recording.
+    #[inline]
+    pub fn into_inner(self) -> AnyReceiver
+    {
+        self.rx
+    }
+
+    #[inline]
+    fn record<T: Clone + Send + 'static>(&mut self, result: Result<T, AnyRecvError>) -> Result<T, AnyRecvError>
+    {
+        if let Ok(t) = &result
+        {
+            self.log.push((Box::new(t.clone()), clone_boxed::<T>));
+        }
+        result
+    }
+}
+
+impl fmt::Debug for RecordingReceiver
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("RecordingReceiver")
+            .field("rx", &self.rx)
+            .field("recorded", &self.log.len())
+            .finish()
+    }
+}
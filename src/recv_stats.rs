@@ -0,0 +1,126 @@
+use super::{type_names, AnyBuffer, BufferedReceiver, RawChannel};
+use core::any::*;
+use std::{cell::Cell, collections::{HashMap, HashSet}, fmt};
+
+/// Counters behind [BufferedReceiver::stats] that are kept as values arrive.
+/// Everything else is read from the receiver when the stats are taken.
+#[derive(Debug, Default)]
+pub(crate) struct StatCounters
+{
+    received: Cell<u64>,
+    spilled: HashMap<TypeId, u64>,
+    high_water: usize
+}
+
+impl StatCounters
+{
+    /// Counts a value taken off the channel.
+    #[inline]
+    pub(crate) fn count_received(&self)
+    {
+        self.received.set(self.received.get() + 1);
+    }
+
+    /// Counts a value of type `type_id` placed in a buffer that now holds
+    /// `len` values.
+    #[inline]
+    pub(crate) fn count_spill(&mut self, type_id: TypeId, len: usize)
+    {
+        *self.spilled.entry(type_id).or_insert(0) += 1;
+        self.high_water = self.high_water.max(len);
+    }
+}
+
+/// What a [BufferedReceiver] has received, taken by
+/// [BufferedReceiver::stats]. Its [Display](fmt::Display) prints a table with
+/// a row per type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecvStats
+{
+    /// Values taken off the channel.
+    pub received: u64,
+    /// Values in the buffer now.
+    pub buffered: usize,
+    /// The most values the buffer has held at once.
+    pub high_water: usize,
+    /// One entry per type seen, sorted by name.
+    pub types: Vec<TypeRecvStats>
+}
+
+/// The part of [RecvStats] about one type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeRecvStats
+{
+    pub type_id: TypeId,
+    /// See [register_type_name](crate::register_type_name).
+    pub name: Option<&'static str>,
+    /// Values handed out. See [BufferedReceiver::recv_counts].
+    pub received: u64,
+    /// Values placed in the buffer.
+    pub spilled: u64,
+    /// Values dropped because the buffer was full. See
+    /// [BufferedReceiver::dropped_counts].
+    pub dropped: u64,
+    /// Values in the buffer now.
+    pub buffered: usize
+}
+
+impl<C: RawChannel, Buf: AnyBuffer> BufferedReceiver<C, Buf>
+{
+    /// Counts of what this receiver has taken off the channel, handed out,
+    /// buffered and dropped, per type, along with the buffer's current size
+    /// and high-water mark. Values received through the `_nobuf` methods
+    /// are counted as taken off the channel but not as handed out.
+    pub fn stats(&self) -> RecvStats
+    {
+        let counts = self.recv_counts();
+        let dropped = self.dropped_counts();
+        let type_ids: HashSet<TypeId> = counts
+            .keys()
+            .chain(self.stats.spilled.keys())
+            .chain(dropped.keys())
+            .copied()
+            .chain(self.buf.types())
+            .collect();
+        let mut types: Vec<TypeRecvStats> = type_ids
+            .into_iter()
+            .map(|type_id| TypeRecvStats
+            {
+                type_id,
+                name: type_names::lookup(type_id),
+                received: counts.get(&type_id).copied().unwrap_or(0),
+                spilled: self.stats.spilled.get(&type_id).copied().unwrap_or(0),
+                dropped: dropped.get(&type_id).copied().unwrap_or(0),
+                buffered: self.buf.len_of(type_id)
+            })
+            .collect();
+        types.sort_by(|a, b| a.name.cmp(&b.name));
+        RecvStats
+        {
+            received: self.stats.received.get(),
+            buffered: self.buf.len(),
+            high_water: self.stats.high_water,
+            types
+        }
+    }
+}
+
+impl fmt::Display for RecvStats
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        writeln!(f, "received from channel: {}", self.received)?;
+        writeln!(f, "buffered: {} (high-water mark {})", self.buffered, self.high_water)?;
+        let names: Vec<String> = self.types
+            .iter()
+            .map(|t| t.name.map_or_else(|| format!("{:?}", t.type_id), String::from))
+            .collect();
+        let width = names.iter().map(String::len).chain([4]).max().unwrap_or(4);
+        write!(f, "{:<width$} | {:>8} | {:>8} | {:>8} | {:>8}", "type", "received", "spilled", "dropped", "buffered", width = width)?;
+        for (t, name) in self.types.iter().zip(names)
+        {
+            write!(f, "\n{:<width$} | {:>8} | {:>8} | {:>8} | {:>8}", name, t.received, t.spilled, t.dropped, t.buffered, width = width)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use super::{mpsc, AnyReceiver, AnyRecvError};
+use core::any::*;
+use std::{borrow::Borrow, sync::atomic::{AtomicUsize, Ordering}, thread, time::{Duration, Instant}};
+
+/// Blocks until any of `receivers` has a message, returning the index of the
+/// receiver it came from along with the message. Each call starts scanning at
+/// a different receiver, so no receiver is always preferred. Returns a
+/// [mpsc::RecvError] once every receiver is disconnected. Use [Select]
+/// directly for strict round-robin across repeated calls.
+#[inline]
+pub fn select(receivers: &[&AnyReceiver]) -> Result<(usize, Box<dyn Any>), AnyRecvError>
+{
+    Select::rotated(receivers).select()
+}
+
+/// Like [select], but gives up with [mpsc::RecvTimeoutError::Timeout] if no
+/// message arrives within `timeout`.
+#[inline]
+pub fn select_timeout(receivers: &[&AnyReceiver], timeout: Duration) -> Result<(usize, Box<dyn Any>), AnyRecvError>
+{
+    Select::rotated(receivers).select_timeout(timeout)
+}
+
+/// Like [select], but downcasts the message to `T`. A message of another
+/// type is returned in a [AnyRecvError::WrongType].
+#[inline]
+pub fn select_recv<T: 'static>(receivers: &[&AnyReceiver]) -> Result<(usize, T), AnyRecvError>
+{
+    Select::rotated(receivers).select_recv()
+}
+
+/// Waits on several [AnyReceiver]s at once.
+///
+/// Since [mpsc] has no native select, receivers are polled with `try_recv`,
+/// backing off from yielding to short sleeps while all of them are empty.
+/// Receivers are polled round-robin: after a message is taken from one
+/// receiver, the next poll starts at the receiver after it, so a receiver
+/// that always has messages ready cannot starve the others.
+#[derive(Debug)]
+pub struct Select<'a>
+{
+    receivers: Vec<&'a AnyReceiver>,
+    next: usize
+}
+
+impl<'a> Select<'a>
+{
+    /// Creates a selector over `receivers`. Indices returned by its methods
+    /// refer to positions in this slice.
+    #[inline]
+    pub fn new(receivers: &[&'a AnyReceiver]) -> Self
+    {
+        Select { receivers: receivers.to_vec(), next: 0 }
+    }
+
+    /// Creates a selector that starts at a different receiver than the last
+    /// one created this way.
+    #[inline]
+    fn rotated(receivers: &[&'a AnyReceiver]) -> Self
+    {
+        static START: AtomicUsize = AtomicUsize::new(0);
+        let next = START.fetch_add(1, Ordering::Relaxed) % receivers.len().max(1);
+        Select { receivers: receivers.to_vec(), next }
+    }
+
+    /// Takes a message from the first ready receiver without blocking.
+    /// Returns [mpsc::TryRecvError::Empty] if none are ready, or
+    /// [mpsc::TryRecvError::Disconnected] if all are disconnected.
+    #[inline]
+    pub fn try_select(&mut self) -> Result<(usize, Box<dyn Any>), AnyRecvError>
+    {
+        try_select_in(&self.receivers, &mut self.next)
+    }
+
+    /// Blocks until any receiver has a message. See [select].
+    #[inline]
+    pub fn select(&mut self) -> Result<(usize, Box<dyn Any>), AnyRecvError>
+    {
+        select_in(&self.receivers, &mut self.next)
+    }
+
+    /// Blocks until any receiver has a message or `timeout` elapses. See
+    /// [select_timeout].
+    #[inline]
+    pub fn select_timeout(&mut self, timeout: Duration) -> Result<(usize, Box<dyn Any>), AnyRecvError>
+    {
+        select_timeout_in(&self.receivers, &mut self.next, timeout)
+    }
+
+    /// Blocks until any receiver has a message and downcasts it to `T`. See
+    /// [select_recv].
+    pub fn select_recv<T: 'static>(&mut self) -> Result<(usize, T), AnyRecvError>
+    {
+        self.select()
+            .and_then(|(index, r)| match r.downcast()
+            {
+                Ok(r) => Ok((index, *r)),
+                Err(r) => Err(AnyRecvError::wrong_type(r)),
+            })
+    }
+}
+
+/// Round-robin polling shared by [Select] and [crate::MergedReceiver]. `next` is 
+/// the index to start polling at, and is moved past the receiver a message
+/// is taken from.
+pub(crate) fn try_select_in<R: Borrow<AnyReceiver>>(receivers: &[R], next: &mut usize) -> Result<(usize, Box<dyn Any>), AnyRecvError>
+{
+    let len = receivers.len();
+    let mut disconnected = 0;
+    for offset in 0..len
+    {
+        let index = (*next + offset) % len;
+        match receivers[index].borrow().try_recv_any()
+        {
+            Ok(r) =>
+            {
+                *next = index + 1;
+                return Ok((index, r));
+            },
+            Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Disconnected)) => disconnected += 1,
+            Err(_) => ()
+        }
+    }
+    match disconnected == len
+    {
+        true => Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Disconnected)),
+        false => Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty))
+    }
+}
+
+/// Blocking version of [try_select_in].
+pub(crate) fn select_in<R: Borrow<AnyReceiver>>(receivers: &[R], next: &mut usize) -> Result<(usize, Box<dyn Any>), AnyRecvError>
+{
+    let mut backoff = Backoff::default();
+    loop
+    {
+        match try_select_in(receivers, next)
+        {
+            Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty)) => backoff.wait(None),
+            Err(_) => break Err(AnyRecvError::RecvError(mpsc::DISCONNECTED)),
+            ok => break ok
+        }
+    }
+}
+
+/// Version of [select_in] that gives up once `timeout` elapses.
+pub(crate) fn select_timeout_in<R: Borrow<AnyReceiver>>(receivers: &[R], next: &mut usize, timeout: Duration) -> Result<(usize, Box<dyn Any>), AnyRecvError>
+{
+    let start = Instant::now();
+    let mut backoff = Backoff::default();
+    loop
+    {
+        match try_select_in(receivers, next)
+        {
+            Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty)) =>
+            {
+                match timeout.checked_sub(start.elapsed())
+                {
+                    Some(remaining) if !remaining.is_zero() => backoff.wait(Some(remaining)),
+                    _ => break Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Timeout))
+                }
+            },
+            Err(_) => break Err(AnyRecvError::RecvTimeoutError(mpsc::RecvTimeoutError::Disconnected)),
+            ok => break ok
+        }
+    }
+}
+
+/// Wait strategy used while every receiver is empty: yield a few times, then
+/// sleep for exponentially longer periods up to a millisecond.
+#[derive(Debug, Default)]
+struct Backoff
+{
+    step: u32
+}
+
+impl Backoff
+{
+    const YIELD_STEPS: u32 = 8;
+    const MAX_SLEEP: Duration = Duration::from_millis(1);
+
+    fn wait(&mut self, limit: Option<Duration>)
+    {
+        if self.step < Self::YIELD_STEPS
+        {
+            thread::yield_now();
+        }
+        else
+        {
+            let sleep = Duration::from_micros(1 << (self.step - Self::YIELD_STEPS).min(10)).min(Self::MAX_SLEEP);
+            thread::sleep(limit.map_or(sleep, |limit| sleep.min(limit)));
+        }
+        self.step = self.step.saturating_add(1);
+    }
+}
@@ -0,0 +1,268 @@
+use super::{mpsc::{self, Sender, Receiver}, trace, type_names, AnyRecvError};
+use core::any::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, error::Error, fmt, sync::Arc, time::Duration};
+
+/// A serialized value: the tag its type was registered under, and its 
+/// `bincode` encoding.
+pub type SerMessage = (String, Vec<u8>);
+
+type Decoder = fn(&[u8]) -> bincode::Result<Box<dyn Any>>;
+type Encoder = fn(&dyn Any) -> bincode::Result<Vec<u8>>;
+
+/// The types a [SerSender] may send and a [SerReceiver] may receive, each 
+/// keyed by a string tag. Both ends must register a type under the same tag,
+/// even if they are in different processes.
+#[derive(Debug, Default)]
+pub struct SerRegistry
+{
+    decoders: HashMap<String, Decoder>,
+    encoders: HashMap<TypeId, Encoder>,
+    tags: HashMap<TypeId, String>
+}
+
+impl SerRegistry
+{
+    /// Creates a registry with no types.
+    #[inline]
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// Registers `T` under `tag`, replacing any type previously registered 
+    /// under it.
+    pub fn register<T: Serialize + DeserializeOwned + 'static>(&mut self, tag: impl Into<String>) -> &mut Self
+    {
+        let tag = tag.into();
+        if self.decoders.insert(tag.clone(), decode::<T>).is_some()
+        {
+            let encoders = &mut self.encoders;
+            self.tags.retain(|type_id, t| match *t == tag
+            {
+                true => { encoders.remove(type_id); false },
+                false => true
+            });
+        }
+        type_names::record::<T>();
+        self.encoders.insert(TypeId::of::<T>(), encode::<T>);
+        self.tags.insert(TypeId::of::<T>(), tag);
+        self
+    }
+
+    /// The tag `T` is registered under, if any.
+    #[inline]
+    pub fn tag_of<T: 'static>(&self) -> Option<&str>
+    {
+        self.tags.get(&TypeId::of::<T>()).map(String::as_str)
+    }
+
+    /// Whether a type is registered with the given [TypeId].
+    #[inline]
+    pub fn contains(&self, type_id: TypeId) -> bool
+    {
+        self.tags.contains_key(&type_id)
+    }
+
+    /// Serializes a value of a registered type into a [SerMessage], or 
+    /// returns `None` if its type is not registered.
+    #[cfg(feature = "buf_recv")]
+    pub(crate) fn encode_dyn(&self, t: &dyn Any) -> Option<bincode::Result<SerMessage>>
+    {
+        let type_id = t.type_id();
+        let (tag, encoder) = (self.tags.get(&type_id)?, self.encoders.get(&type_id)?);
+        Some(encoder(t).map(|bytes| (tag.clone(), bytes)))
+    }
+
+    /// Deserializes a [SerMessage] by its tag.
+    pub(crate) fn decode(&self, (tag, bytes): SerMessage) -> Result<Box<dyn Any>, SerRecvError>
+    {
+        match self.decoders.get(&tag)
+        {
+            Some(decoder) => decoder(&bytes).map_err(|err| SerRecvError::Decode(tag, err)),
+            None => Err(SerRecvError::UnknownTag(tag))
+        }
+    }
+}
+
+fn decode<T: DeserializeOwned + 'static>(bytes: &[u8]) -> bincode::Result<Box<dyn Any>>
+{
+    bincode::deserialize::<T>(bytes).map(|t| Box::new(t) as Box<dyn Any>)
+}
+
+fn encode<T: Serialize + 'static>(t: &dyn Any) -> bincode::Result<Vec<u8>>
+{
+    bincode::serialize(t.downcast_ref::<T>().expect("encoder called with a value of the wrong type"))
+}
+
+/// An [mpsc::channel] of serialized values, for values of any type registered
+/// in `registry`. The [SerMessage]s on the channel may instead be carried 
+/// between processes by pairing [SerSender::new] and [SerReceiver::new] with
+/// any transport.
+#[inline]
+pub fn ser_channel(registry: SerRegistry) -> (SerSender, SerReceiver)
+{
+    let (tx, rx) = mpsc::channel();
+    let registry = Arc::new(registry);
+    (SerSender::new(tx, registry.clone()), SerReceiver::new(rx, registry))
+}
+
+/// Serializes values of registered types and sends them as [SerMessage]s.
+#[derive(Debug, Clone)]
+pub struct SerSender(pub Sender<SerMessage>, pub Arc<SerRegistry>);
+
+impl SerSender
+{
+    /// Creates a sender that serializes the types in `registry` onto `tx`.
+    #[inline]
+    pub fn new(tx: Sender<SerMessage>, registry: Arc<SerRegistry>) -> Self
+    {
+        Self(tx, registry)
+    }
+
+    /// Serializes `t` and wraps [mpsc::Sender::send].
+    pub fn send<T: Serialize + 'static>(&self, t: T) -> Result<(), SerSendError>
+    {
+        let tag = self.1.tags.get(&TypeId::of::<T>()).ok_or_else(|| SerSendError::Unregistered(type_name::<T>()))?;
+        let bytes = bincode::serialize(&t).map_err(SerSendError::Encode)?;
+        trace::send::<T>();
+        self.0.send((tag.clone(), bytes)).map_err(SerSendError::SendError)
+    }
+}
+
+/// Receives [SerMessage]s and deserializes them by tag.
+#[derive(Debug)]
+pub struct SerReceiver(pub Receiver<SerMessage>, pub Arc<SerRegistry>);
+
+impl SerReceiver
+{
+    /// Creates a receiver that deserializes the types in `registry` from `rx`.
+    #[inline]
+    pub fn new(rx: Receiver<SerMessage>, registry: Arc<SerRegistry>) -> Self
+    {
+        Self(rx, registry)
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. A value of a registered type other than
+    /// `T` is returned in [AnyRecvError::WrongType].
+    #[inline]
+    pub fn recv<T: 'static>(&self) -> Result<T, SerRecvError>
+    {
+        self.downcast(self.0.recv().map_err(AnyRecvError::RecvError))
+    }
+
+    /// Wraps [mpsc::Receiver::recv_timeout].
+    #[inline]
+    pub fn recv_timeout<T: 'static>(&self, timeout: Duration) -> Result<T, SerRecvError>
+    {
+        self.downcast(self.0.recv_timeout(timeout).map_err(AnyRecvError::RecvTimeoutError))
+    }
+
+    /// Wraps [mpsc::Receiver::try_recv].
+    #[inline]
+    pub fn try_recv<T: 'static>(&self) -> Result<T, SerRecvError>
+    {
+        self.downcast(self.0.try_recv().map_err(AnyRecvError::TryRecvError))
+    }
+
+    /// Wraps [mpsc::Receiver::recv]. Deserializes a value of any registered 
+    /// type.
+    #[inline]
+    pub fn recv_any(&self) -> Result<Box<dyn Any>, SerRecvError>
+    {
+        self.1.decode(self.0.recv().map_err(AnyRecvError::RecvError)?)
+    }
+
+    fn downcast<T: 'static>(&self, msg: Result<SerMessage, AnyRecvError>) -> Result<T, SerRecvError>
+    {
+        let result = match msg
+        {
+            Ok(msg) => self.1.decode(msg)?.downcast().map(|r| *r).map_err(AnyRecvError::wrong_type),
+            Err(err) => Err(err)
+        };
+        trace::recv(&result);
+        Ok(result?)
+    }
+}
+
+/// An error sending on a [SerSender].
+#[derive(Debug)]
+pub enum SerSendError
+{
+    /// The type, named here, is not in the sender's [SerRegistry].
+    Unregistered(&'static str),
+    Encode(bincode::Error),
+    SendError(mpsc::SendError<SerMessage>)
+}
+
+impl fmt::Display for SerSendError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            SerSendError::Unregistered(name) => write!(f, "Type is not registered: {}", name),
+            SerSendError::Encode(err) => write!(f, "Failed to serialize: {}", err),
+            SerSendError::SendError(err) => err.fmt(f)
+        }
+    }
+}
+
+impl Error for SerSendError
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        match self
+        {
+            SerSendError::Unregistered(_) => None,
+            SerSendError::Encode(err) => Some(err),
+            SerSendError::SendError(err) => Some(err)
+        }
+    }
+}
+
+/// An error receiving on a [SerReceiver].
+#[derive(Debug)]
+pub enum SerRecvError
+{
+    RecvError(AnyRecvError),
+    /// No type is registered under the received tag.
+    UnknownTag(String),
+    /// The value sent under the tag could not be deserialized.
+    Decode(String, bincode::Error)
+}
+
+impl From<AnyRecvError> for SerRecvError
+{
+    #[inline]
+    fn from(err: AnyRecvError) -> Self
+    {
+        SerRecvError::RecvError(err)
+    }
+}
+
+impl fmt::Display for SerRecvError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            SerRecvError::RecvError(err) => err.fmt(f),
+            SerRecvError::UnknownTag(tag) => write!(f, "Received unknown tag: {}", tag),
+            SerRecvError::Decode(tag, err) => write!(f, "Failed to deserialize {}: {}", tag, err)
+        }
+    }
+}
+
+impl Error for SerRecvError
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        match self
+        {
+            SerRecvError::RecvError(err) => Some(err),
+            SerRecvError::UnknownTag(_) => None,
+            SerRecvError::Decode(_, err) => Some(err)
+        }
+    }
+}
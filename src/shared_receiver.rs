@@ -0,0 +1,124 @@
+use super::{mpsc, AnySender, AnyReceiver, AnyRecvError};
+#[cfg(feature = "buf_recv")]
+use super::BufferedReceiver;
+use core::any::*;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+
+/// An [mpsc::channel] that supports dynamic typing and may be received from
+/// by several threads at once.
+#[inline]
+pub fn shared_channel() -> (AnySender, SharedReceiver)
+{
+    let (tx, rx) = super::channel();
+    (tx, SharedReceiver(Arc::new(Mutex::new(rx))))
+}
+
+/// Wraps an [AnyReceiver] in an [Arc]<[Mutex]> so that it can be cloned and
+/// shared between worker threads. Every message is delivered to exactly one
+/// of the clones.
+#[derive(Debug, Clone)]
+pub struct SharedReceiver(pub Arc<Mutex<AnyReceiver>>);
+
+impl SharedReceiver
+{
+    /// Locks the receiver and wraps [AnyReceiver::recv]. Other clones will
+    /// block on the lock until a value arrives.
+    #[inline]
+    pub fn recv<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        self.lock().recv()
+    }
+
+    /// Locks the receiver and wraps [AnyReceiver::recv_timeout]. Time spent
+    /// waiting for the lock is not counted against `timeout`.
+    #[inline]
+    pub fn recv_timeout<T: 'static>(&self, timeout: std::time::Duration) -> Result<T, AnyRecvError>
+    {
+        self.lock().recv_timeout(timeout)
+    }
+
+    /// Wraps [AnyReceiver::try_recv]. If another clone is currently holding
+    /// the lock this returns [mpsc::TryRecvError::Empty] rather than waiting.
+    #[inline]
+    pub fn try_recv<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        match self.0.try_lock()
+        {
+            Ok(rx) => rx.try_recv(),
+            Err(TryLockError::Poisoned(err)) => err.into_inner().try_recv(),
+            Err(TryLockError::WouldBlock) => Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty))
+        }
+    }
+
+    /// Locks the receiver and wraps [AnyReceiver::recv_any].
+    #[inline]
+    pub fn recv_any(&self) -> Result<Box<dyn Any>, AnyRecvError>
+    {
+        self.lock().recv_any()
+    }
+
+    /// No receive can leave the [AnyReceiver] in an inconsistent state, so a
+    /// poisoned lock is simply recovered.
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, AnyReceiver>
+    {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Wraps a [BufferedReceiver] in an [Arc]<[Mutex]> so that it can be cloned 
+/// and shared between worker threads. Every message is delivered to exactly
+/// one of the clones, and values parked in the buffer by one clone can be 
+/// received by any of them.
+#[cfg(feature = "buf_recv")]
+#[derive(Debug, Clone)]
+pub struct SharedBufferedReceiver(pub Arc<Mutex<BufferedReceiver>>);
+
+#[cfg(feature = "buf_recv")]
+impl SharedBufferedReceiver
+{
+    /// Locks the receiver and wraps [BufferedReceiver::recv]. Other clones 
+    /// will block on the lock until a value arrives.
+    #[inline]
+    pub fn recv<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        self.lock().recv()
+    }
+
+    /// Locks the receiver and wraps [BufferedReceiver::recv_timeout]. Time 
+    /// spent waiting for the lock is not counted against `timeout`.
+    #[inline]
+    pub fn recv_timeout<T: 'static>(&self, timeout: std::time::Duration) -> Result<T, AnyRecvError>
+    {
+        self.lock().recv_timeout(timeout)
+    }
+
+    /// Wraps [BufferedReceiver::try_recv]. If another clone is currently 
+    /// holding the lock this returns [mpsc::TryRecvError::Empty] rather than
+    /// waiting.
+    #[inline]
+    pub fn try_recv<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        match self.0.try_lock()
+        {
+            Ok(mut rx) => rx.try_recv(),
+            Err(TryLockError::Poisoned(err)) => err.into_inner().try_recv(),
+            Err(TryLockError::WouldBlock) => Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty))
+        }
+    }
+
+    /// Locks the receiver and wraps [BufferedReceiver::recv_buf].
+    #[inline]
+    pub fn recv_buf<T: 'static>(&self) -> Result<T, AnyRecvError>
+    {
+        self.lock().recv_buf()
+    }
+
+    /// No receive can leave the [BufferedReceiver] in an inconsistent state,
+    /// so a poisoned lock is simply recovered.
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, BufferedReceiver>
+    {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,125 @@
+use super::{type_names, AnyBuffer, BufferedReceiver, RawChannel, SerMessage, SerRecvError};
+use core::any::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{error::Error, fmt};
+
+impl<C: RawChannel, Buf: AnyBuffer> BufferedReceiver<C, Buf>
+{
+    /// Allows buffered values of type `T` to be saved by
+    /// [BufferedReceiver::snapshot_buffer] and loaded by
+    /// [BufferedReceiver::restore_buffer]. `T` is tagged with its
+    /// [type_name], so a snapshot can only be restored by a build in which
+    /// that name is unchanged.
+    #[inline]
+    pub fn register_serializable<T: Serialize + DeserializeOwned + 'static>(&mut self)
+    {
+        self.ser.register::<T>(type_name::<T>());
+    }
+
+    /// Serializes every value in the internal buffer with `bincode`, as a
+    /// list of [SerMessage]s tagged by type name. Values of the same type
+    /// keep their order. Every buffered type must have been registered with
+    /// [BufferedReceiver::register_serializable]; if any have not, nothing is
+    /// serialized and their names are returned in
+    /// [SnapshotError::Unregistered].
+    pub fn snapshot_buffer(&self) -> Result<Vec<u8>, SnapshotError>
+    {
+        let types = self.buf.types();
+        let unregistered: Vec<String> = types
+            .iter()
+            .filter(|type_id| !self.ser.contains(**type_id))
+            .map(|type_id| type_names::TypeName(*type_id).to_string())
+            .collect();
+        if !unregistered.is_empty()
+        {
+            return Err(SnapshotError::Unregistered(unregistered));
+        }
+        let mut entries: Vec<SerMessage> = Vec::with_capacity(self.buf.len());
+        for type_id in types
+        {
+            for t in self.buf.iter_dyn(type_id)
+            {
+                let entry = self.ser.encode_dyn(t).expect("type was checked to be registered");
+                entries.push(entry.map_err(SnapshotError::Encode)?);
+            }
+        }
+        bincode::serialize(&entries).map_err(SnapshotError::Encode)
+    }
+
+    /// Deserializes a snapshot taken by [BufferedReceiver::snapshot_buffer]
+    /// and places its values in the internal buffer, after any already there.
+    /// Its types must have been registered with
+    /// [BufferedReceiver::register_serializable]. Nothing is buffered unless
+    /// every value can be deserialized. Returns the number of values
+    /// buffered, which is fewer than the snapshot held if the
+    /// [BufferLimit](crate::BufferLimit) drops some.
+    pub fn restore_buffer(&mut self, bytes: &[u8]) -> Result<usize, SnapshotError>
+    {
+        let entries: Vec<SerMessage> = bincode::deserialize(bytes).map_err(SnapshotError::Malformed)?;
+        let values = entries
+            .into_iter()
+            .map(|entry| self.ser.decode(entry))
+            .collect::<Result<Vec<_>, _>>()?;
+        let before = self.buf.len();
+        for r in values
+        {
+            self.spill(r);
+        }
+        Ok(self.buf.len().saturating_sub(before))
+    }
+}
+
+/// An error saving or restoring a [BufferedReceiver]'s buffer.
+#[derive(Debug)]
+pub enum SnapshotError
+{
+    /// These buffered types, named here, have not been registered.
+    Unregistered(Vec<String>),
+    Encode(bincode::Error),
+    /// The bytes are not a snapshot.
+    Malformed(bincode::Error),
+    /// No type is registered under this tag.
+    UnknownTag(String),
+    /// The value saved under the tag could not be deserialized.
+    Decode(String, bincode::Error)
+}
+
+impl From<SerRecvError> for SnapshotError
+{
+    fn from(err: SerRecvError) -> Self
+    {
+        match err
+        {
+            SerRecvError::UnknownTag(tag) => SnapshotError::UnknownTag(tag),
+            SerRecvError::Decode(tag, err) => SnapshotError::Decode(tag, err),
+            SerRecvError::RecvError(err) => unreachable!("decoding does not receive: {}", err)
+        }
+    }
+}
+
+impl fmt::Display for SnapshotError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            SnapshotError::Unregistered(names) => write!(f, "Types are not registered: {}", names.join(", ")),
+            SnapshotError::Encode(err) => write!(f, "Failed to serialize: {}", err),
+            SnapshotError::Malformed(err) => write!(f, "Malformed snapshot: {}", err),
+            SnapshotError::UnknownTag(tag) => write!(f, "Snapshot holds unknown tag: {}", tag),
+            SnapshotError::Decode(tag, err) => write!(f, "Failed to deserialize {}: {}", tag, err)
+        }
+    }
+}
+
+impl Error for SnapshotError
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        match self
+        {
+            SnapshotError::Unregistered(_) | SnapshotError::UnknownTag(_) => None,
+            SnapshotError::Encode(err) | SnapshotError::Malformed(err) | SnapshotError::Decode(_, err) => Some(err)
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use super::{mpsc, AnyReceiver, AnyRecvError};
+
+/// What a [SpinReceiver] does once it has spun `max_spins` times without
+/// receiving anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SpinFallback
+{
+    /// Returns [mpsc::TryRecvError::Empty].
+    #[default]
+    GiveUp,
+    /// Blocks on [AnyReceiver::recv].
+    Block
+}
+
+/// Wraps an [AnyReceiver] to poll it in a busy loop rather than parking the
+/// thread, for callers that cannot afford the latency of being woken up.
+/// Created with [AnyReceiver::into_spin].
+#[derive(Debug)]
+pub struct SpinReceiver
+{
+    pub rx: AnyReceiver,
+    pub fallback: SpinFallback
+}
+
+impl SpinReceiver
+{
+    /// Wraps `rx`, following `fallback` once a spin gives up.
+    #[inline]
+    pub fn new(rx: AnyReceiver, fallback: SpinFallback) -> Self
+    {
+        SpinReceiver { rx, fallback }
+    }
+
+    /// Wraps [AnyReceiver::try_recv], retrying up to `max_spins` times while
+    /// the channel is empty, with a [std::hint::spin_loop] between tries. Any
+    /// other result, including a value of the wrong type, is returned at
+    /// once. If every try finds the channel empty, follows the
+    /// [SpinFallback].
+    pub fn spin_recv<T: 'static>(&self, max_spins: u32) -> Result<T, AnyRecvError>
+    {
+        for _ in 0..max_spins
+        {
+            match self.rx.try_recv()
+            {
+                Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty)) => std::hint::spin_loop(),
+                result => return result
+            }
+        }
+        match self.fallback
+        {
+            SpinFallback::GiveUp => Err(AnyRecvError::TryRecvError(mpsc::TryRecvError::Empty)),
+            SpinFallback::Block => self.rx.recv()
+        }
+    }
+
+    /// Unwraps the [AnyReceiver].
+    #[inline]
+    pub fn into_inner(self) -> AnyReceiver
+    {
+        self.rx
+    }
+}
+
+impl AnyReceiver
+{
+    /// Converts this receiver into a [SpinReceiver] that follows `fallback`
+    /// once a spin gives up.
+    #[inline]
+    pub fn into_spin(self, fallback: SpinFallback) -> SpinReceiver
+    {
+        SpinReceiver::new(self, fallback)
+    }
+}
@@ -0,0 +1,80 @@
+use super::{AnyReceiver, AnySender};
+use core::any::*;
+use std::{collections::HashMap, fmt};
+
+pub(crate) type Cloner = fn(&dyn Any) -> Box<dyn Any>;
+
+/// A monitor channel that is sent a copy of every value a receiver hands 
+/// out. Only types with a registered cloner are copied.
+#[derive(Default)]
+pub(crate) struct Tap
+{
+    tx: Option<AnySender>,
+    cloners: HashMap<TypeId, Cloner>
+}
+
+impl Tap
+{
+    #[inline]
+    pub(crate) fn set(&mut self, tx: AnySender)
+    {
+        self.tx = Some(tx);
+    }
+
+    #[inline]
+    pub(crate) fn register_clone<T: Clone + 'static>(&mut self)
+    {
+        self.cloners.insert(TypeId::of::<T>(), clone_boxed::<T>);
+    }
+
+    /// Sends a copy of `r` to the tap, if there is one and `r`'s type has a 
+    /// cloner.
+    #[inline]
+    pub(crate) fn copy(&self, r: &dyn Any)
+    {
+        if let (Some(tx), Some(cloner)) = (&self.tx, self.cloners.get(&r.type_id()))
+        {
+            // A monitor that has gone away should not disturb the consumer
+            let _ = tx.0.send(cloner(r));
+        }
+    }
+}
+
+pub(crate) fn clone_boxed<T: Clone + 'static>(r: &dyn Any) -> Box<dyn Any>
+{
+    Box::new(r.downcast_ref::<T>().expect("cloner called with a value of the wrong type").clone())
+}
+
+impl fmt::Debug for Tap
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("Tap")
+            .field("tx", &self.tx)
+            .field("cloners", &self.cloners.len())
+            .finish()
+    }
+}
+
+impl AnyReceiver
+{
+    /// Sends a copy of every value this receiver hands out to `tx`, 
+    /// replacing any previous tap. This includes values returned inside 
+    /// [AnyRecvError::WrongType](crate::AnyRecvError::WrongType). Values are
+    /// copied by the cloners registered with 
+    /// [AnyReceiver::register_tap_clone]; values of other types pass through
+    /// untapped. Values taken by `into_stream` are not tapped.
+    #[inline]
+    pub fn tap(&mut self, tx: AnySender)
+    {
+        self.3.set(tx);
+    }
+
+    /// Allows values of type `T` to be copied to the tap by cloning them. See
+    /// [AnyReceiver::tap].
+    #[inline]
+    pub fn register_tap_clone<T: Clone + 'static>(&mut self)
+    {
+        self.3.register_clone::<T>();
+    }
+}
@@ -0,0 +1,47 @@
+use super::{AnyReceiver, AnyRecvError, AnySender, mpsc};
+use core::any::*;
+use std::{ops::Deref, time::Instant};
+
+/// A value paired with the time it was sent, as sent by 
+/// [AnySender::send_timestamped]. `sent_at.elapsed()` gives the time the 
+/// value has spent in flight.
+#[derive(Debug, Clone)]
+pub struct TimestampedMessage<T>
+{
+    pub value: T,
+    pub sent_at: Instant
+}
+
+impl<T> Deref for TimestampedMessage<T>
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T
+    {
+        &self.value
+    }
+}
+
+impl AnySender
+{
+    /// Like [AnySender::send], but wraps `t` in a [TimestampedMessage] 
+    /// stamped with the current time.
+    #[inline]
+    pub fn send_timestamped<T: Any>(&self, t: T) -> Result<(), mpsc::SendError<Box<dyn Any>>>
+    {
+        self.send(TimestampedMessage { value: t, sent_at: Instant::now() })
+    }
+}
+
+impl AnyReceiver
+{
+    /// Receives a value sent by [AnySender::send_timestamped]. A value sent
+    /// any other way, or of a type other than `T`, is returned in a 
+    /// [AnyRecvError::WrongType].
+    #[inline]
+    pub fn recv_timestamped<T: 'static>(&self) -> Result<TimestampedMessage<T>, AnyRecvError>
+    {
+        self.recv()
+    }
+}
@@ -0,0 +1,86 @@
+//! Hooks called on every send and receive. With the `tracing` feature they 
+//! emit `tracing` events; without it they compile to nothing.
+use super::AnyRecvError;
+use core::any::*;
+
+#[cfg(feature = "tracing")]
+use super::type_names;
+
+/// Called by every send, with the type being sent.
+#[cfg(feature = "tracing")]
+#[inline]
+pub(crate) fn send<T: Any>()
+{
+    tracing::trace!(type_name = type_name::<T>(), "send");
+}
+
+/// Called with the result of every typed receive.
+#[cfg(feature = "tracing")]
+#[inline]
+pub(crate) fn recv<T: 'static>(result: &Result<T, AnyRecvError>)
+{
+    match result
+    {
+        Ok(_) => tracing::trace!(type_name = type_name::<T>(), "recv"),
+        Err(err) => recv_failed(err)
+    }
+}
+
+/// Called with the result of every untyped receive.
+#[cfg(feature = "tracing")]
+#[inline]
+pub(crate) fn recv_any(result: &Result<Box<dyn Any>, AnyRecvError>)
+{
+    match result
+    {
+        Ok(r) => tracing::trace!(type_name = type_names::lookup(r.as_ref().type_id()).unwrap_or("<unknown>"), "recv"),
+        Err(err) => recv_failed(err)
+    }
+}
+
+/// Called whenever a value of an unrequested type is placed in a buffer.
+#[cfg(all(feature = "tracing", feature = "buf_recv"))]
+#[inline]
+pub(crate) fn spill(type_id: TypeId)
+{
+    tracing::debug!(type_name = type_names::lookup(type_id).unwrap_or("<unknown>"), "buffered value of unrequested type");
+}
+
+#[cfg(feature = "tracing")]
+fn recv_failed(err: &AnyRecvError)
+{
+    let error = match err
+    {
+        AnyRecvError::RecvError(_) => "RecvError",
+        AnyRecvError::RecvTimeoutError(_) => "RecvTimeoutError",
+        AnyRecvError::TryRecvError(_) => "TryRecvError",
+        AnyRecvError::WrongType(..) => "WrongType",
+        AnyRecvError::Cancelled => "Cancelled",
+        AnyRecvError::Poisoned => "Poisoned",
+        #[cfg(feature = "buf_recv")]
+        AnyRecvError::BufRecvError(_) => "BufRecvError",
+        #[cfg(feature = "buf_recv")]
+        AnyRecvError::EmptyBuffer => "EmptyBuffer",
+        #[cfg(feature = "buf_recv")]
+        AnyRecvError::BufferFull(_) => "BufferFull",
+        #[cfg(feature = "buf_recv")]
+        AnyRecvError::BufferLimitReached(_) => "BufferLimitReached",
+    };
+    tracing::trace!(error, "recv failed");
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn send<T: Any>() {}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn recv<T: 'static>(_: &Result<T, AnyRecvError>) {}
+
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn recv_any(_: &Result<Box<dyn Any>, AnyRecvError>) {}
+
+#[cfg(all(not(feature = "tracing"), feature = "buf_recv"))]
+#[inline(always)]
+pub(crate) fn spill(_: TypeId) {}
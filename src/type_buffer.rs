@@ -0,0 +1,279 @@
+use super::AnyBuffer;
+use core::any::*;
+use std::collections::{HashMap, VecDeque};
+
+/// A FIFO buffer of dynamically typed values. Values are queued separately
+/// per type, so removing a value of one type never disturbs the order of
+/// values of any other type.
+#[derive(Debug, Default)]
+pub struct TypeBuffer
+{
+    queues: HashMap<TypeId, VecDeque<Box<dyn Any>>>,
+    len: usize
+}
+
+impl TypeBuffer
+{
+    /// Creates an empty buffer.
+    #[inline]
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// Creates an empty buffer with room for values of at least `capacity`
+    /// distinct types before it reallocates. Values of each type are queued
+    /// separately and grow as needed.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self
+    {
+        TypeBuffer { queues: HashMap::with_capacity(capacity), len: 0 }
+    }
+
+    /// Number of distinct types the buffer can hold values of without 
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize
+    {
+        self.queues.capacity()
+    }
+
+    /// Reserves room for values of at least `additional` more distinct types.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize)
+    {
+        self.queues.reserve(additional)
+    }
+
+    /// Total number of buffered values, across all types.
+    #[inline]
+    pub fn len(&self) -> usize
+    {
+        self.len
+    }
+
+    /// Whether no values are buffered.
+    #[inline]
+    pub fn is_empty(&self) -> bool
+    {
+        self.len == 0
+    }
+
+    /// Whether any values with the given [TypeId] are buffered.
+    #[inline]
+    pub fn contains(&self, type_id: TypeId) -> bool
+    {
+        self.queues.contains_key(&type_id)
+    }
+
+    /// The distinct types of the buffered values, in the same unspecified 
+    /// order [TypeBuffer::remove_matching] considers them.
+    #[inline]
+    pub fn types(&self) -> impl Iterator<Item = TypeId> + '_
+    {
+        self.queues.keys().copied()
+    }
+
+    /// Pushes a value onto the back of the queue for its type.
+    #[inline]
+    pub fn insert<T: Any>(&mut self, t: T)
+    {
+        self.insert_dyn(Box::new(t))
+    }
+
+    /// Pushes an already boxed value onto the back of the queue for its
+    /// underlying type.
+    #[inline]
+    pub fn insert_dyn(&mut self, t: Box<dyn Any>)
+    {
+        self.queues
+            .entry(t.as_ref().type_id())
+            .or_default()
+            .push_back(t);
+        self.len += 1;
+    }
+
+    /// Pops the oldest value of type `T`, if any.
+    #[inline]
+    pub fn remove<T: Any>(&mut self) -> Option<T>
+    {
+        self.remove_dyn(TypeId::of::<T>())
+            .map(|t| *t.downcast().expect("TypeBuffer queue contained a value of the wrong type"))
+    }
+
+    /// Pops the oldest value with the given [TypeId], if any.
+    pub fn remove_dyn(&mut self, type_id: TypeId) -> Option<Box<dyn Any>>
+    {
+        let queue = self.queues.get_mut(&type_id)?;
+        let t = queue.pop_front();
+        if queue.is_empty()
+        {
+            self.queues.remove(&type_id);
+        }
+        if t.is_some()
+        {
+            self.len -= 1;
+        }
+        t
+    }
+
+    /// Returns a reference to the oldest value of type `T` without removing
+    /// it, if any.
+    #[inline]
+    pub fn peek<T: Any>(&self) -> Option<&T>
+    {
+        self.queues
+            .get(&TypeId::of::<T>())?
+            .front()?
+            .downcast_ref()
+    }
+
+    /// Removes the oldest value of type `T` that passes `pred`, if any. Other
+    /// values of type `T` keep their order.
+    pub fn remove_where<T: Any>(&mut self, pred: impl Fn(&T) -> bool) -> Option<T>
+    {
+        AnyBuffer::remove_where(self, pred)
+    }
+
+    /// Pops the oldest value of the first buffered type that passes `pred`.
+    /// If several buffered types pass, which one is chosen is unspecified.
+    pub fn remove_matching(&mut self, pred: impl Fn(TypeId) -> bool) -> Option<Box<dyn Any>>
+    {
+        let type_id = self.queues.keys().copied().find(|type_id| pred(*type_id))?;
+        self.remove_dyn(type_id)
+    }
+
+    /// Removes and returns every buffered value. Values of the same type stay
+    /// in the order they were inserted; the order between types is 
+    /// unspecified.
+    pub fn drain(&mut self) -> Vec<Box<dyn Any>>
+    {
+        self.len = 0;
+        self.queues
+            .drain()
+            .flat_map(|(_type_id, queue)| queue)
+            .collect()
+    }
+}
+
+impl AnyBuffer for TypeBuffer
+{
+    #[inline]
+    fn insert_dyn(&mut self, t: Box<dyn Any>)
+    {
+        TypeBuffer::insert_dyn(self, t)
+    }
+
+    #[inline]
+    fn find_type(&self, pred: &dyn Fn(TypeId) -> bool) -> Option<TypeId>
+    {
+        self.types().find(|type_id| pred(*type_id))
+    }
+
+    fn remove_where_dyn(&mut self, type_id: TypeId, pred: &dyn Fn(&dyn Any) -> bool) -> Option<Box<dyn Any>>
+    {
+        let queue = self.queues.get_mut(&type_id)?;
+        let index = queue.iter().position(|t| pred(t.as_ref()))?;
+        let t = queue.remove(index)?;
+        if queue.is_empty()
+        {
+            self.queues.remove(&type_id);
+        }
+        self.len -= 1;
+        Some(t)
+    }
+
+    #[inline]
+    fn iter_dyn(&self, type_id: TypeId) -> Box<dyn Iterator<Item = &dyn Any> + '_>
+    {
+        Box::new(self.queues
+            .get(&type_id)
+            .into_iter()
+            .flatten()
+            .map(|t| t.as_ref()))
+    }
+
+    #[inline]
+    fn peek_dyn(&self, type_id: TypeId) -> Option<&dyn Any>
+    {
+        self.queues
+            .get(&type_id)?
+            .front()
+            .map(|t| t.as_ref())
+    }
+
+    #[inline]
+    fn peek_dyn_mut(&mut self, type_id: TypeId) -> Option<&mut dyn Any>
+    {
+        self.queues
+            .get_mut(&type_id)?
+            .front_mut()
+            .map(|t| t.as_mut())
+    }
+
+    #[inline]
+    fn len(&self) -> usize
+    {
+        self.len
+    }
+
+    #[inline]
+    fn len_of(&self, type_id: TypeId) -> usize
+    {
+        self.queues.get(&type_id).map_or(0, VecDeque::len)
+    }
+
+    #[inline]
+    fn types(&self) -> Vec<TypeId>
+    {
+        TypeBuffer::types(self).collect()
+    }
+
+    #[inline]
+    fn clear(&mut self)
+    {
+        self.queues.clear();
+        self.len = 0;
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize)
+    {
+        TypeBuffer::reserve(self, additional)
+    }
+
+    #[inline]
+    fn remove_dyn(&mut self, type_id: TypeId) -> Option<Box<dyn Any>>
+    {
+        TypeBuffer::remove_dyn(self, type_id)
+    }
+
+    fn clear_of(&mut self, type_id: TypeId) -> usize
+    {
+        let removed = self.queues.remove(&type_id).map_or(0, |queue| queue.len());
+        self.len -= removed;
+        removed
+    }
+
+    fn retain(&mut self, pred: &mut dyn FnMut(TypeId, &dyn Any) -> bool)
+    {
+        self.queues.retain(|type_id, queue|
+        {
+            queue.retain(|t| pred(*type_id, t.as_ref()));
+            !queue.is_empty()
+        });
+        self.len = self.queues.values().map(VecDeque::len).sum();
+    }
+
+    fn remove_any_where(&mut self, pred: &dyn Fn(&dyn Any) -> bool) -> Option<Box<dyn Any>>
+    {
+        let type_id = self.types().find(|type_id| self.queues[type_id].iter().any(|t| pred(t.as_ref())))?;
+        self.remove_where_dyn(type_id, pred)
+    }
+
+    #[inline]
+    fn drain(&mut self) -> Vec<Box<dyn Any>>
+    {
+        TypeBuffer::drain(self)
+    }
+}
@@ -0,0 +1,70 @@
+use core::any::*;
+use std::{collections::HashMap, sync::{OnceLock, PoisonError, RwLock}};
+
+/// Process-wide map from [TypeId] to [type_name], filled in by 
+/// [register_type_name], and as types are sent with the `type_names` feature.
+static TYPE_NAMES: OnceLock<RwLock<HashMap<TypeId, &'static str>>> = OnceLock::new();
+
+#[inline]
+fn type_names() -> &'static RwLock<HashMap<TypeId, &'static str>>
+{
+    TYPE_NAMES.get_or_init(Default::default)
+}
+
+/// Records the name of `T` so it can later be looked up by [TypeId]. Only
+/// takes the write lock the first time a type is seen.
+#[inline]
+pub(crate) fn record<T: Any>()
+{
+    let type_id = TypeId::of::<T>();
+    let known = type_names()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .contains_key(&type_id);
+    if !known
+    {
+        type_names()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(type_id, type_name::<T>());
+    }
+}
+
+/// Records the name of `T`, so that errors about values of type `T`, such
+/// as [AnyRecvError::BufRecvError](crate::AnyRecvError::BufRecvError),
+/// display it instead of its [TypeId]. With the `type_names` feature, types
+/// sent by [AnySender::send](crate::AnySender::send) are recorded 
+/// automatically, at the cost of a read lock on a process-wide map per send.
+#[inline]
+pub fn register_type_name<T: Any>()
+{
+    record::<T>();
+}
+
+/// Looks up the name of a type that has previously been recorded.
+#[inline]
+pub(crate) fn lookup(type_id: TypeId) -> Option<&'static str>
+{
+    type_names()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&type_id)
+        .copied()
+}
+
+/// Displays the recorded name of a type, or its [TypeId] if there is none.
+#[cfg(feature = "buf_recv")]
+pub(crate) struct TypeName(pub(crate) TypeId);
+
+#[cfg(feature = "buf_recv")]
+impl std::fmt::Display for TypeName
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match lookup(self.0)
+        {
+            Some(name) => f.write_str(name),
+            None => write!(f, "{:?}", self.0)
+        }
+    }
+}
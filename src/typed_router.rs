@@ -0,0 +1,104 @@
+use super::{mpsc, AnyReceiver, AnySender, ReceiverExt};
+use std::{sync::{Arc, atomic::{AtomicBool, Ordering}}, thread::{self, JoinHandle}, time::Duration};
+
+/// What a router started by [AnyReceiver::into_typed] does with a message 
+/// that is not of the routed type.
+#[derive(Debug)]
+pub enum Fallback
+{
+    /// Drop the message.
+    Drop,
+    /// Panic in debug builds, stopping the router. Drops the message in 
+    /// release builds.
+    DebugPanic,
+    /// Forward the message, still boxed, to another channel. The message is
+    /// dropped if that channel is disconnected.
+    Forward(AnySender)
+}
+
+/// How long the router waits for a message before checking whether it has 
+/// been asked to stop.
+pub(crate) const STOP_POLL: Duration = Duration::from_millis(10);
+
+impl AnyReceiver
+{
+    /// Converts this receiver into a plain [mpsc::Receiver] of `T`, so that
+    /// downstream code does not need this crate. A router thread downcasts 
+    /// each incoming message and forwards it to the returned receiver, 
+    /// handing messages of any other type to `fallback`.
+    ///
+    /// The typed channel is unbounded, so the router never applies 
+    /// backpressure: if the typed receiver falls behind, messages queue up 
+    /// in it rather than on this channel. The router stops once every sender
+    /// is gone, once the typed receiver is dropped, or when asked to through
+    /// the returned [TypedRouterHandle].
+    pub fn into_typed<T: Send + 'static>(self, fallback: Fallback) -> (mpsc::Receiver<T>, TypedRouterHandle)
+    {
+        let (tx, typed_rx) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let thread = thread::spawn(move ||
+        {
+            while !thread_stop.load(Ordering::SeqCst)
+            {
+                let r = match self.recv_timeout_msg(STOP_POLL)
+                {
+                    Ok(r) => r,
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(_) => break
+                };
+                match r.downcast::<T>()
+                {
+                    Ok(t) => if tx.send(*t).is_err()
+                    {
+                        break;
+                    },
+                    Err(r) => match &fallback
+                    {
+                        Fallback::Drop => (),
+                        Fallback::DebugPanic => debug_assert!(false, "into_typed router received a value of the wrong type"),
+                        Fallback::Forward(other) => { let _ = other.0.send(r); }
+                    }
+                }
+            }
+            self
+        });
+        (typed_rx, TypedRouterHandle { stop, thread })
+    }
+}
+
+/// Controls the router thread started by [AnyReceiver::into_typed]. Dropping
+/// the handle leaves the router running.
+#[derive(Debug)]
+pub struct TypedRouterHandle
+{
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<AnyReceiver>
+}
+
+impl TypedRouterHandle
+{
+    /// Asks the router to stop. It finishes forwarding the message it is 
+    /// handling, if any, and stops within a few milliseconds.
+    #[inline]
+    pub fn stop(&self)
+    {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the router has stopped.
+    #[inline]
+    pub fn is_finished(&self) -> bool
+    {
+        self.thread.is_finished()
+    }
+
+    /// Waits for the router to stop and returns the original receiver, 
+    /// with any messages the router did not take still on it. Returns an
+    /// error if the router panicked.
+    #[inline]
+    pub fn join(self) -> thread::Result<AnyReceiver>
+    {
+        self.thread.join()
+    }
+}